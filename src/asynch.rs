@@ -0,0 +1,1317 @@
+//! Async driver for the IQS231A/B, built on the [embedded-hal-async](https://crates.io/crates/embedded-hal-async) traits.
+//!
+//! The API mirrors the blocking [`Iqs231xDriver`](crate::Iqs231xDriver) and shares its data types,
+//! so both can be used in the same build.
+
+use core::marker::PhantomData;
+
+use crate::iqs231x::{
+    AtiConfig, AtiResult, ChannelData, CommunicationMode, DeviceInfo, Event, EventChanges, Events, InitReport, ADDR_RANGE, DEFAULT_ADDR, MAX_WRITE_LEN,
+};
+use crate::registers::{
+    Commands, Counts, DebugEvents, FilterSettings, HaltTime, InvalidValue, Lta, MovementThreshold, OtpShadow, PowerMode, PowerSettings,
+    ProxThreshold, QuickRelease, Register, ReportRate, SystemFlags, TouchThreshold, ATI_MULTIPLIERS, ATI_SETTINGS, CONFIG_LEN, CONFIG_START, COUNTS, LTA,
+    OTP_BANK_COUNT, OTP_SHADOW_0, PRODUCT_NUMBER, SYSTEM_FLAGS,
+};
+use crate::config::{ConfigShadow, Iqs231xConfig};
+use crate::otp::OtpImage;
+#[cfg(feature = "otp-write")]
+use crate::otp::{self, OtpWriteToken, OTP_PROGRAM_TIME_US};
+#[cfg(feature = "otp-write")]
+use crate::registers::COMMANDS;
+use crate::ready::{NoPin, WaitReady};
+use crate::retry::{NoDelay, RetryPolicy};
+use crate::state::{Configurable, Configured, Running, Transition, Uninitialized, ATI_POLL_INTERVAL_US, ATI_TIMEOUT_US, POWER_UP_TIME_US};
+use crate::Iqs231xError;
+use embedded_hal::i2c::SevenBitAddress;
+
+/// A running driver returned by [`init`](Iqs231xDriver::init), with the report of the start-up sequence.
+pub type Initialized<I2C, D, RDY> = (Iqs231xDriver<I2C, D, RDY, Running>, InitReport);
+
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Iqs231xDriver<I2C, D = NoDelay, RDY = NoPin, S = Uninitialized> {
+    address: SevenBitAddress,
+    i2c: I2C,
+    delay: D,
+    retry: RetryPolicy,
+    ready: RDY,
+    shadow: Option<ConfigShadow>,
+    state: PhantomData<S>,
+}
+
+#[warn(missing_docs)]
+impl <I2C> Iqs231xDriver<I2C> {
+    /// Creates a new IQS231X driver instance with the default I2C address (0x44).
+    ///
+    /// If a custom address is needed, use the [`with_address`](Iqs231xDriver::with_address) function instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::asynch::Iqs231xDriver;
+    /// # let i2c_interface = embedded_hal_mock::eh1::i2c::Mock::new(&[]);
+    ///
+    /// let sensor = Iqs231xDriver::new(i2c_interface);
+    /// # sensor.release_inner().done();
+    /// ```
+    pub fn new(i2c: I2C) -> Self {
+        Self::with_address(i2c, DEFAULT_ADDR)
+    }
+
+    /// Creates a new driver instance with a custom I2C address.
+    pub fn with_address(i2c: I2C, addr: SevenBitAddress) -> Self {
+        Self {
+            address: addr,
+            i2c,
+            delay: NoDelay,
+            retry: RetryPolicy::NONE,
+            ready: NoPin,
+            shadow: None,
+            state: PhantomData,
+        }
+    }
+}
+
+#[warn(missing_docs)]
+impl<I2C, D, RDY, S> Iqs231xDriver<I2C, D, RDY, S> {
+    /// Retries NACKed transactions according to `policy`, waiting with `delay` between attempts.
+    pub fn with_retry<D2>(self, delay: D2, policy: RetryPolicy) -> Iqs231xDriver<I2C, D2, RDY, S> {
+        Iqs231xDriver {
+            address: self.address,
+            i2c: self.i2c,
+            delay,
+            retry: policy,
+            ready: self.ready,
+            shadow: self.shadow,
+            state: self.state,
+        }
+    }
+
+    /// Waits for the communication window on the device's ready (IO) line before every transaction.
+    ///
+    /// The line is active low and is awaited without a timeout: unlike the blocking driver,
+    /// every call hangs forever if the device never pulls the line low, for example when it is
+    /// unpowered or held in reset. Wrap calls in the executor's timeout (such as
+    /// `embassy_time::with_timeout`) if the device may not respond.
+    pub fn with_ready_pin<P>(self, pin: P) -> Iqs231xDriver<I2C, D, P, S> {
+        Iqs231xDriver {
+            address: self.address,
+            i2c: self.i2c,
+            delay: self.delay,
+            retry: self.retry,
+            ready: pin,
+            shadow: self.shadow,
+            state: self.state,
+        }
+    }
+
+
+    /// Updates the retry policy, keeping the delay.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry = policy;
+    }
+
+    /// Returns the current retry policy.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry
+    }
+
+    /// Returns the cached configuration, if the shadow is enabled.
+    ///
+    /// Contains the changes not yet written by [`flush`](Self::flush).
+    pub fn shadow_config(&self) -> Option<Iqs231xConfig> {
+        // The shadow only holds contents validated by `enable_shadow` and the setters.
+        self.shadow.and_then(|shadow| Iqs231xConfig::from_bytes(*shadow.bytes()).ok())
+    }
+
+    /// Returns `true` if the shadow holds changes not yet written by [`flush`](Self::flush).
+    pub fn is_dirty(&self) -> bool {
+        self.shadow.is_some_and(|shadow| shadow.is_dirty())
+    }
+
+    /// Stops caching the configuration registers, discarding any changes not yet flushed.
+    pub fn disable_shadow(&mut self) {
+        self.shadow = None;
+    }
+
+    /// Updates the device's I2C address.
+    ///
+    /// Only the driver's copy changes, use [`program_address`](Self::program_address) to move the device.
+    pub fn set_address(&mut self, addr: SevenBitAddress) {
+        self.address = addr;
+    }
+
+    /// Returns the current I2C address of the device.
+    pub fn address(&self) -> SevenBitAddress {
+        self.address
+    }
+
+    /// Consumes the driver and returns the underlying I2C peripheral.
+    pub fn release_inner(self) -> I2C {
+        self.i2c
+    }
+
+    fn into_state<S2>(self) -> Iqs231xDriver<I2C, D, RDY, S2> {
+        Iqs231xDriver {
+            address: self.address,
+            i2c: self.i2c,
+            delay: self.delay,
+            retry: self.retry,
+            ready: self.ready,
+            shadow: self.shadow,
+            state: PhantomData,
+        }
+    }
+}
+
+#[warn(missing_docs)]
+impl<I2C, D, RDY> Iqs231xDriver<I2C, D, RDY, Uninitialized> {
+    /// Moves straight to the [`Running`] state, for a device that is already sensing.
+    ///
+    /// Use this for devices running from their OTP settings, or when the device was
+    /// started before the MCU reset.
+    pub fn assume_running(self) -> Iqs231xDriver<I2C, D, RDY, Running> {
+        self.into_state()
+    }
+}
+
+#[warn(missing_docs)]
+impl<I2C, D, RDY> Iqs231xDriver<I2C, D, RDY, Running> {
+    /// Moves back to the [`Configured`] state to change settings.
+    ///
+    /// The new settings are only tuned for once [`start`](Iqs231xDriver::start) re-runs ATI.
+    pub fn reconfigure(self) -> Iqs231xDriver<I2C, D, RDY, Configured> {
+        self.into_state()
+    }
+}
+
+impl<I2C, E> Iqs231xDriver<I2C>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    E: embedded_hal::i2c::Error,
+{
+    /// Creates a new driver instance with the default I2C address, after checking that
+    /// the device answering at the address is an IQS231.
+    ///
+    /// Returns [`Iqs231xError::WrongDevice`] if another device answers.
+    pub async fn probe(i2c: I2C) -> Result<Self, Iqs231xError<E>> {
+        Self::probe_with_address(i2c, DEFAULT_ADDR).await
+    }
+
+    /// Creates a new driver instance with a custom I2C address, after checking that
+    /// the device answering at the address is an IQS231.
+    ///
+    /// Returns [`Iqs231xError::WrongDevice`] if another device answers.
+    pub async fn probe_with_address(i2c: I2C, addr: SevenBitAddress) -> Result<Self, Iqs231xError<E>> {
+        let mut driver = Self::with_address(i2c, addr);
+        driver.identify().await?;
+
+        Ok(driver)
+    }
+}
+
+impl<I2C, D, RDY, S, E> Iqs231xDriver<I2C, D, RDY, S>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    D: embedded_hal_async::delay::DelayNs,
+    RDY: WaitReady,
+    E: embedded_hal::i2c::Error,
+{
+    pub async fn product_number(&mut self) -> Result<u8, Iqs231xError<E>> {
+        let mut results: [u8; 2] = [0, 0];
+
+        self.read_registers(PRODUCT_NUMBER, &mut results).await?;
+
+        Ok(results[1])
+    }
+
+    /// Reads the device information and checks that the device is an IQS231.
+    ///
+    /// Returns [`Iqs231xError::WrongDevice`] if another device answers.
+    pub async fn identify(&mut self) -> Result<DeviceInfo, Iqs231xError<E>> {
+        let info = self.device_info().await?;
+
+        if !info.is_iqs231() {
+            return Err(Iqs231xError::WrongDevice {
+                register: PRODUCT_NUMBER,
+                product_number: info.product_number,
+            });
+        }
+
+        Ok(info)
+    }
+
+    /// Reads the product and software number of the device in one transaction.
+    pub async fn device_info(&mut self) -> Result<DeviceInfo, Iqs231xError<E>> {
+        let mut results: [u8; 3] = [0; 3];
+
+        self.read_registers(PRODUCT_NUMBER, &mut results).await?;
+
+        Ok(DeviceInfo::from_bytes(results))
+    }
+
+    /// Moves the device to another I2C address in [`ADDR_RANGE`], through the address
+    /// selection in the shadow of OTP bank 0.
+    ///
+    /// The driver only switches to `addr` once the device identifies itself there. The
+    /// setting is lost at power-down unless it is programmed into OTP.
+    pub async fn program_address(&mut self, addr: SevenBitAddress) -> Result<(), Iqs231xError<E>> {
+        if !ADDR_RANGE.contains(&addr) {
+            return Err(Iqs231xError::InvalidValue(InvalidValue { register: OTP_SHADOW_0, value: u16::from(addr) }));
+        }
+
+        let mut shadow = [0; 1];
+        self.read_registers(OTP_SHADOW_0, &mut shadow).await?;
+
+        let selection = (shadow[0] & !OtpShadow::ADDRESS_MASK) | (addr - DEFAULT_ADDR);
+        self.write_registers(OTP_SHADOW_0, &[selection]).await?;
+
+        self.follow_address(addr).await
+    }
+
+    /// Switches the driver to `addr` once the device identifies itself there, keeping the
+    /// previous address on failure.
+    async fn follow_address(&mut self, addr: SevenBitAddress) -> Result<(), Iqs231xError<E>> {
+        let previous = core::mem::replace(&mut self.address, addr);
+        if let Err(e) = self.identify().await {
+            self.address = previous;
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Reads the OTP banks from their shadow registers, in one transaction.
+    ///
+    /// The shadows are loaded from OTP at reset, [`program_address`](Self::program_address)
+    /// changes the shadow of bank 0.
+    pub async fn read_otp(&mut self) -> Result<OtpImage, Iqs231xError<E>> {
+        let mut results = [0; OTP_BANK_COUNT];
+
+        self.read_registers(OTP_SHADOW_0, &mut results).await?;
+
+        Ok(OtpImage(results))
+    }
+
+    /// Waits for the communication window, signalled by the ready line going low.
+    async fn wait_ready(&mut self, register: u8) -> Result<(), Iqs231xError<E>> {
+        self.ready.wait_ready().await.map_err(|kind| Iqs231xError::ReadyPin { register, kind })
+    }
+
+    /// Reads consecutive registers starting at `register` into `buffer`.
+    async fn read_registers(&mut self, register: u8, buffer: &mut [u8]) -> Result<(), Iqs231xError<E>> {
+        let mut attempt = 1;
+
+        loop {
+            self.wait_ready(register).await?;
+
+            let Err(e) = self.i2c.write_read(self.address, &[register], buffer).await else {
+                break;
+            };
+
+            if !self.retry.should_retry(attempt, e.kind()) {
+                return Err(e.into());
+            }
+
+            trace!("iqs231x@{:#04x}: read {:#04x} NACKed, attempt {}", self.address, register, attempt);
+            self.delay.delay_us(self.retry.backoff_us).await;
+            attempt += 1;
+        }
+
+        trace!("iqs231x@{:#04x}: read {:#04x}: {:?}", self.address, register, buffer);
+
+        Ok(())
+    }
+
+    /// Writes `payload` to consecutive registers starting at `register`, in one transaction.
+    async fn write_registers(&mut self, register: u8, payload: &[u8]) -> Result<(), Iqs231xError<E>> {
+        let mut buffer = [0u8; MAX_WRITE_LEN + 1];
+        buffer[0] = register;
+        buffer[1..=payload.len()].copy_from_slice(payload);
+
+        let mut attempt = 1;
+
+        loop {
+            self.wait_ready(register).await?;
+
+            let Err(e) = self.i2c.write(self.address, &buffer[..=payload.len()]).await else {
+                break;
+            };
+
+            if !self.retry.should_retry(attempt, e.kind()) {
+                return Err(e.into());
+            }
+
+            trace!("iqs231x@{:#04x}: write {:#04x} NACKed, attempt {}", self.address, register, attempt);
+            self.delay.delay_us(self.retry.backoff_us).await;
+            attempt += 1;
+        }
+
+        trace!("iqs231x@{:#04x}: write {:#04x}: {:?}", self.address, register, payload);
+
+        Ok(())
+    }
+
+    async fn read_cached(&mut self, register: u8, buf: &mut [u8]) -> Result<(), Iqs231xError<E>> {
+        if let Some(shadow) = &self.shadow
+            && shadow.read(register, buf)
+        {
+            return Ok(());
+        }
+
+        self.read_registers(register, buf).await
+    }
+
+    async fn write_cached(&mut self, register: u8, payload: &[u8]) -> Result<(), Iqs231xError<E>> {
+        if let Some(shadow) = &mut self.shadow
+            && shadow.write(register, payload)
+        {
+            return Ok(());
+        }
+
+        self.write_registers(register, payload).await
+    }
+
+    async fn read_register<R: Register>(&mut self) -> Result<R, Iqs231xError<E>> {
+        let mut result: [u8; 1] = [0];
+
+        self.read_cached(R::ADDRESS, &mut result).await?;
+
+        R::from_raw(result[0]).map_err(Iqs231xError::InvalidValue)
+    }
+
+    async fn write_register<R: Register>(&mut self, value: R) -> Result<(), Iqs231xError<E>> {
+        self.write_cached(R::ADDRESS, &[value.into_raw()]).await?;
+
+        Ok(())
+    }
+
+    /// Writes `commands` to the command register, triggering every set command.
+    pub(crate) async fn send_command(&mut self, commands: Commands) -> Result<(), Iqs231xError<E>> {
+        self.write_register(commands).await
+    }
+
+    /// Acknowledges a device reset, clearing [`SystemFlags::show_reset`](crate::registers::SystemFlags::show_reset).
+    pub async fn acknowledge_reset(&mut self) -> Result<(), Iqs231xError<E>> {
+        self.send_command(Commands { ack_reset: true, ..Default::default() }).await
+    }
+
+    /// Resets the device, returning to the [`Uninitialized`] state.
+    ///
+    /// The device restarts with the settings loaded from OTP, the shadow is disabled.
+    /// On failure the driver is returned unchanged along with the error.
+    pub async fn soft_reset(mut self) -> Transition<Self, Iqs231xDriver<I2C, D, RDY, Uninitialized>, E> {
+        match self.send_command(Commands { soft_reset: true, ..Default::default() }).await {
+            Ok(()) => {
+                self.shadow = None;
+                Ok(self.into_state())
+            }
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    /// Reads the proximity threshold.
+    pub async fn prox_threshold(&mut self) -> Result<ProxThreshold, Iqs231xError<E>> {
+        self.read_register().await
+    }
+
+    /// Reads the touch threshold.
+    pub async fn touch_threshold(&mut self) -> Result<TouchThreshold, Iqs231xError<E>> {
+        self.read_register().await
+    }
+
+    /// Reads the movement threshold.
+    pub async fn movement_threshold(&mut self) -> Result<MovementThreshold, Iqs231xError<E>> {
+        self.read_register().await
+    }
+
+    /// Reads the ATI settings and target in one transaction.
+    pub async fn ati_config(&mut self) -> Result<AtiConfig, Iqs231xError<E>> {
+        let mut results: [u8; 2] = [0; 2];
+
+        self.read_cached(ATI_SETTINGS, &mut results).await?;
+
+        Ok(AtiConfig::from_bytes(results))
+    }
+
+    /// Reads the power mode and report rate.
+    pub async fn power_settings(&mut self) -> Result<PowerSettings, Iqs231xError<E>> {
+        self.read_register().await
+    }
+
+    /// Reads the power mode.
+    pub async fn power_mode(&mut self) -> Result<PowerMode, Iqs231xError<E>> {
+        Ok(self.power_settings().await?.power_mode)
+    }
+
+    /// Reads the report rate.
+    pub async fn report_rate(&mut self) -> Result<ReportRate, Iqs231xError<E>> {
+        Ok(self.power_settings().await?.report_rate)
+    }
+
+    /// Reads the debug events of the device.
+    pub async fn debug_events(&mut self) -> Result<DebugEvents, Iqs231xError<E>> {
+        self.read_register().await
+    }
+
+    /// Reads the quick-release settings.
+    pub async fn quick_release(&mut self) -> Result<QuickRelease, Iqs231xError<E>> {
+        self.read_register().await
+    }
+
+    /// Reads the halt time of the LTA.
+    pub async fn halt_time(&mut self) -> Result<HaltTime, Iqs231xError<E>> {
+        self.read_register().await
+    }
+
+    /// Reads the counts and LTA filter settings.
+    pub async fn filter_settings(&mut self) -> Result<FilterSettings, Iqs231xError<E>> {
+        self.read_register().await
+    }
+
+    /// Reads the whole configuration block in one transaction.
+    ///
+    /// Fails with [`Iqs231xError::InvalidValue`] if a register holds a value outside the
+    /// range of its setting, such as the zero thresholds of a blank device.
+    pub async fn read_config(&mut self) -> Result<Iqs231xConfig, Iqs231xError<E>> {
+        let mut results = [0; CONFIG_LEN];
+
+        self.read_registers(CONFIG_START, &mut results).await?;
+
+        Iqs231xConfig::from_bytes(results).map_err(Iqs231xError::InvalidValue)
+    }
+
+    /// Reads the multipliers and compensation selected by the last ATI.
+    pub async fn ati_result(&mut self) -> Result<AtiResult, Iqs231xError<E>> {
+        let mut results: [u8; 2] = [0; 2];
+
+        self.read_registers(ATI_MULTIPLIERS, &mut results).await?;
+
+        Ok(AtiResult::from_bytes(results))
+    }
+
+    /// Polls the system flags every [`ATI_POLL_INTERVAL_US`] with `delay` until ATI completes,
+    /// returning the time waited in microseconds.
+    async fn wait_for_ati<DL>(&mut self, delay: &mut DL) -> Result<u32, Iqs231xError<E>>
+    where
+        DL: embedded_hal_async::delay::DelayNs,
+    {
+        let mut waited_us = 0;
+
+        while !self.ati_done().await? {
+            if waited_us >= ATI_TIMEOUT_US {
+                return Err(Iqs231xError::Timeout { register: SYSTEM_FLAGS });
+            }
+
+            delay.delay_us(ATI_POLL_INTERVAL_US).await;
+            waited_us = waited_us.saturating_add(ATI_POLL_INTERVAL_US);
+        }
+
+        Ok(waited_us)
+    }
+
+    /// Returns `true` once ATI completed, failing with [`Iqs231xError::AtiFailed`] if it could not reach its target.
+    async fn ati_done(&mut self) -> Result<bool, Iqs231xError<E>> {
+        let flags: SystemFlags = self.read_register().await?;
+
+        if flags.ati_error {
+            return Err(Iqs231xError::AtiFailed { register: SYSTEM_FLAGS });
+        }
+
+        Ok(!flags.ati_busy)
+    }
+}
+
+impl<I2C, D, RDY, S, E> Iqs231xDriver<I2C, D, RDY, S>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    D: embedded_hal_async::delay::DelayNs,
+    RDY: WaitReady,
+    S: Configurable,
+    E: embedded_hal::i2c::Error,
+{
+    /// Switches the device to the given communication mode.
+    pub async fn set_communication_mode(&mut self, mode: CommunicationMode) -> Result<(), Iqs231xError<E>> {
+        self.send_command(mode.command()).await
+    }
+
+    /// Sets the proximity threshold.
+    pub async fn set_prox_threshold(&mut self, threshold: ProxThreshold) -> Result<(), Iqs231xError<E>> {
+        self.write_register(threshold).await
+    }
+
+    /// Sets the touch threshold.
+    pub async fn set_touch_threshold(&mut self, threshold: TouchThreshold) -> Result<(), Iqs231xError<E>> {
+        self.write_register(threshold).await
+    }
+
+    /// Sets the movement threshold.
+    pub async fn set_movement_threshold(&mut self, threshold: MovementThreshold) -> Result<(), Iqs231xError<E>> {
+        self.write_register(threshold).await
+    }
+
+    /// Writes the ATI settings and target in one transaction.
+    ///
+    /// The configuration takes effect on the next ATI, see [`redo_ati`](Self::redo_ati).
+    pub async fn set_ati_config(&mut self, config: AtiConfig) -> Result<(), Iqs231xError<E>> {
+        let payload = config.to_bytes();
+
+        self.write_cached(ATI_SETTINGS, &payload).await?;
+
+        Ok(())
+    }
+
+    /// Writes the power mode and report rate.
+    pub async fn set_power_settings(&mut self, settings: PowerSettings) -> Result<(), Iqs231xError<E>> {
+        self.write_register(settings).await
+    }
+
+    /// Sets the power mode, keeping the report rate.
+    pub async fn set_power_mode(&mut self, mode: PowerMode) -> Result<(), Iqs231xError<E>> {
+        let mut settings = self.power_settings().await?;
+        settings.power_mode = mode;
+
+        self.set_power_settings(settings).await
+    }
+
+    /// Sets the report rate, keeping the power mode.
+    pub async fn set_report_rate(&mut self, rate: ReportRate) -> Result<(), Iqs231xError<E>> {
+        let mut settings = self.power_settings().await?;
+        settings.report_rate = rate;
+
+        self.set_power_settings(settings).await
+    }
+
+    /// Writes the quick-release settings.
+    pub async fn set_quick_release(&mut self, settings: QuickRelease) -> Result<(), Iqs231xError<E>> {
+        self.write_register(settings).await
+    }
+
+    /// Sets the halt time of the LTA.
+    pub async fn set_halt_time(&mut self, halt_time: HaltTime) -> Result<(), Iqs231xError<E>> {
+        self.write_register(halt_time).await
+    }
+
+    /// Writes the counts and LTA filter settings.
+    pub async fn set_filter_settings(&mut self, settings: FilterSettings) -> Result<(), Iqs231xError<E>> {
+        self.write_register(settings).await
+    }
+
+    /// Writes the whole configuration block in one transaction.
+    pub async fn write_config(&mut self, config: &Iqs231xConfig) -> Result<(), Iqs231xError<E>> {
+        let payload = config.to_bytes();
+
+        self.write_registers(CONFIG_START, &payload).await?;
+
+        if self.shadow.is_some() {
+            self.shadow = Some(ConfigShadow::new(payload));
+        }
+
+        Ok(())
+    }
+
+    /// Writes the whole configuration block, then reads it back and compares it.
+    ///
+    /// Reserved bits are ignored, see [`CONFIG_VERIFY_MASK`](crate::config::CONFIG_VERIFY_MASK).
+    /// Fails with [`Iqs231xError::VerifyMismatch`] naming the first register that differs.
+    pub async fn write_config_verified(&mut self, config: &Iqs231xConfig) -> Result<(), Iqs231xError<E>> {
+        let mut results = [0; CONFIG_LEN];
+
+        self.write_config(config).await?;
+        self.read_registers(CONFIG_START, &mut results).await?;
+
+        config.verify(&results)
+    }
+
+    /// Reads the configuration block and starts caching it in the driver.
+    ///
+    /// Fails like [`read_config`](Self::read_config) if the block holds an invalid value.
+    ///
+    /// While the shadow is enabled the configuration getters and setters only access the
+    /// cache, the changes are written to the device by [`flush`](Self::flush).
+    /// [`read_config`](Self::read_config) and [`write_config`](Self::write_config) still access the device.
+    pub async fn enable_shadow(&mut self) -> Result<(), Iqs231xError<E>> {
+        let mut results = [0; CONFIG_LEN];
+
+        self.read_registers(CONFIG_START, &mut results).await?;
+        Iqs231xConfig::from_bytes(results).map_err(Iqs231xError::InvalidValue)?;
+        self.shadow = Some(ConfigShadow::new(results));
+
+        Ok(())
+    }
+
+    /// Writes the configuration registers changed in the shadow, one transaction per run of
+    /// consecutive registers.
+    ///
+    /// Does nothing if the shadow is disabled.
+    pub async fn flush(&mut self) -> Result<(), Iqs231xError<E>> {
+        let Some(mut shadow) = self.shadow else {
+            return Ok(());
+        };
+
+        while let Some(range) = shadow.next_dirty() {
+            self.write_registers(CONFIG_START + range.start as u8, &shadow.bytes()[range.clone()]).await?;
+            shadow.mark_synced(range);
+            self.shadow = Some(shadow);
+        }
+
+        Ok(())
+    }
+
+    /// Burns the image named by `token` into the OTP banks and verifies it.
+    ///
+    /// Writes the image to the shadow registers and follows the device to the address
+    /// selected by the image once it identifies itself there. If it does not, the previous
+    /// shadow contents are written back and nothing is programmed.
+    ///
+    /// Then programs the banks, waits [`OTP_PROGRAM_TIME_US`] with `delay` and soft-resets the
+    /// device, so it reloads the shadows from OTP. After [`POWER_UP_TIME_US`] the reloaded
+    /// shadows are read back, failing with [`Iqs231xError::VerifyMismatch`] naming the first
+    /// bank that differs. The device restarts with the programmed settings and the
+    /// configuration shadow is disabled, like after [`soft_reset`](Self::soft_reset).
+    #[cfg(feature = "otp-write")]
+    pub async fn program_otp<DL>(&mut self, delay: &mut DL, token: OtpWriteToken) -> Result<(), Iqs231xError<E>>
+    where
+        DL: embedded_hal_async::delay::DelayNs,
+    {
+        let image = token.image();
+        let previous = self.read_otp().await?;
+
+        self.write_registers(OTP_SHADOW_0, &image.0).await?;
+        if let Err(e) = self.follow_address(image.address()).await {
+            let _ = self.write_registers(OTP_SHADOW_0, &previous.0).await;
+            return Err(e);
+        }
+
+        self.write_registers(COMMANDS, &[otp::PROGRAM_COMMAND]).await?;
+        delay.delay_us(OTP_PROGRAM_TIME_US).await;
+
+        self.send_command(Commands { soft_reset: true, ..Default::default() }).await?;
+        self.shadow = None;
+        delay.delay_us(POWER_UP_TIME_US).await;
+        self.follow_address(image.address()).await?;
+
+        let found = self.read_otp().await?;
+        otp::verify(&image, &found)
+    }
+
+    /// Writes `config` and moves to the [`Configured`] state.
+    ///
+    /// On failure the driver is returned unchanged along with the error.
+    pub async fn configure(mut self, config: &Iqs231xConfig) -> Transition<Self, Iqs231xDriver<I2C, D, RDY, Configured>, E> {
+        match self.write_config(config).await {
+            Ok(()) => Ok(self.into_state()),
+            Err(e) => Err((self, e)),
+        }
+    }
+}
+
+impl<I2C, D, RDY, E> Iqs231xDriver<I2C, D, RDY, Configured>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    D: embedded_hal_async::delay::DelayNs,
+    RDY: WaitReady,
+    E: embedded_hal::i2c::Error,
+{
+    /// Runs ATI with the written configuration and moves to the [`Running`] state once it completes.
+    ///
+    /// Changes pending in the shadow are flushed first. The ATI progress is polled every
+    /// [`ATI_POLL_INTERVAL_US`] with `delay`, failing with [`Iqs231xError::AtiFailed`], or
+    /// [`Iqs231xError::Timeout`] after [`ATI_TIMEOUT_US`].
+    /// On failure the driver is returned unchanged along with the error.
+    pub async fn start<DL>(mut self, delay: &mut DL) -> Transition<Self, Iqs231xDriver<I2C, D, RDY, Running>, E>
+    where
+        DL: embedded_hal_async::delay::DelayNs,
+    {
+        match self.run_ati(delay).await {
+            Ok(()) => Ok(self.into_state()),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    async fn run_ati<DL>(&mut self, delay: &mut DL) -> Result<(), Iqs231xError<E>>
+    where
+        DL: embedded_hal_async::delay::DelayNs,
+    {
+        self.flush().await?;
+        self.send_command(Commands { redo_ati: true, ..Default::default() }).await?;
+
+        self.wait_for_ati(delay).await?;
+
+        Ok(())
+    }
+}
+
+
+impl<I2C, D, RDY, E> Iqs231xDriver<I2C, D, RDY, Uninitialized>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    D: embedded_hal_async::delay::DelayNs,
+    RDY: WaitReady,
+    E: embedded_hal::i2c::Error,
+{
+    /// Brings up a freshly powered device and moves to the [`Running`] state.
+    ///
+    /// Waits [`POWER_UP_TIME_US`], acknowledges a pending reset, writes and verifies
+    /// `config`, then runs ATI and polls it with `delay` every [`ATI_POLL_INTERVAL_US`]. Fails with
+    /// [`Iqs231xError::AtiFailed`], or [`Iqs231xError::Timeout`] after [`ATI_TIMEOUT_US`].
+    /// On failure the driver is returned unchanged along with the error.
+    pub async fn init<DL>(
+        mut self,
+        delay: &mut DL,
+        config: &Iqs231xConfig,
+    ) -> Transition<Self, Initialized<I2C, D, RDY>, E>
+    where
+        DL: embedded_hal_async::delay::DelayNs,
+    {
+        match self.run_init(delay, config).await {
+            Ok(report) => Ok((self.into_state(), report)),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    async fn run_init<DL>(&mut self, delay: &mut DL, config: &Iqs231xConfig) -> Result<InitReport, Iqs231xError<E>>
+    where
+        DL: embedded_hal_async::delay::DelayNs,
+    {
+        delay.delay_us(POWER_UP_TIME_US).await;
+
+        let flags: SystemFlags = self.read_register().await?;
+        if flags.show_reset {
+            self.acknowledge_reset().await?;
+        }
+
+        self.write_config_verified(config).await?;
+        self.send_command(Commands { redo_ati: true, ..Default::default() }).await?;
+
+        let ati_time_us = self.wait_for_ati(delay).await?;
+
+        Ok(InitReport {
+            reset_acknowledged: flags.show_reset,
+            ati: self.ati_result().await?,
+            ati_time_us,
+        })
+    }
+}
+
+impl<I2C, D, RDY, E> Iqs231xDriver<I2C, D, RDY, Running>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    D: embedded_hal_async::delay::DelayNs,
+    RDY: WaitReady,
+    E: embedded_hal::i2c::Error,
+{
+    /// Reads the system flags and main events of the device in one transaction.
+    ///
+    /// Fails with [`Iqs231xError::DeviceReset`] if the device reset, as it no longer runs the
+    /// configuration of the driver. The error repeats until the reset is acknowledged, see
+    /// [`acknowledge_reset`](Self::acknowledge_reset).
+    pub async fn read_events(&mut self) -> Result<Events, Iqs231xError<E>> {
+        let mut results: [u8; 2] = [0; 2];
+
+        self.read_registers(SYSTEM_FLAGS, &mut results).await?;
+
+        let events = Events::from_bytes(results);
+        if events.device_reset {
+            return Err(Iqs231xError::DeviceReset { register: SYSTEM_FLAGS });
+        }
+
+        Ok(events)
+    }
+
+    /// Reads the raw channel counts.
+    pub async fn counts(&mut self) -> Result<Counts, Iqs231xError<E>> {
+        let mut results: [u8; 2] = [0; 2];
+
+        self.read_registers(COUNTS, &mut results).await?;
+
+        Ok(Counts::from_be_bytes(results))
+    }
+
+    /// Reads the long-term average of the channel counts.
+    pub async fn lta(&mut self) -> Result<Lta, Iqs231xError<E>> {
+        let mut results: [u8; 2] = [0; 2];
+
+        self.read_registers(LTA, &mut results).await?;
+
+        Ok(Lta::from_be_bytes(results))
+    }
+
+    /// Reads the counts and the LTA in one transaction and derives the delta from them.
+    pub async fn read_channel(&mut self) -> Result<ChannelData, Iqs231xError<E>> {
+        let mut results: [u8; 4] = [0; 4];
+
+        self.read_registers(COUNTS, &mut results).await?;
+
+        Ok(ChannelData::from_bytes(results))
+    }
+
+    /// Re-runs the automatic tuning implementation (ATI).
+    ///
+    /// The progress of the ATI is reported by [`Events::ati_busy`] and [`Events::ati_error`].
+    pub async fn redo_ati(&mut self) -> Result<(), Iqs231xError<E>> {
+        self.send_command(Commands { redo_ati: true, ..Default::default() }).await
+    }
+
+    /// Reseeds the long-term average with the current counts.
+    pub async fn reseed(&mut self) -> Result<(), Iqs231xError<E>> {
+        self.send_command(Commands { reseed: true, ..Default::default() }).await
+    }
+}
+
+impl<I2C, D, RDY, E> Iqs231xDriver<I2C, D, RDY, Running>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    D: embedded_hal_async::delay::DelayNs,
+    RDY: embedded_hal_async::digital::Wait,
+    E: embedded_hal::i2c::Error,
+{
+    /// Returns a stream of the [`Event`]s of the device.
+    ///
+    /// The stream sleeps on the ready pin until the device signals an event, so it needs a
+    /// connected line, see [`with_ready_pin`](Self::with_ready_pin). Intended for event mode.
+    ///
+    /// ```rust,compile_fail
+    /// use iqs231x_i2c::asynch::Iqs231xDriver;
+    /// # let i2c_interface = embedded_hal_mock::eh1::i2c::Mock::new(&[]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface).assume_running();
+    /// // Without a ready pin the stream would poll the bus continuously.
+    /// let stream = sensor.event_stream();
+    /// ```
+    pub fn event_stream(&mut self) -> EventStream<'_, I2C, D, RDY> {
+        EventStream {
+            driver: self,
+            previous: Events::default(),
+            pending: EventChanges::default(),
+        }
+    }
+}
+
+/// Stream of the [`Event`]s of the device, returned by [`event_stream`](Iqs231xDriver::event_stream).
+#[derive(Debug)]
+pub struct EventStream<'a, I2C, D, RDY> {
+    driver: &'a mut Iqs231xDriver<I2C, D, RDY, Running>,
+    previous: Events,
+    pending: EventChanges,
+}
+
+impl<I2C, D, RDY, E> EventStream<'_, I2C, D, RDY>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    D: embedded_hal_async::delay::DelayNs,
+    RDY: embedded_hal_async::digital::Wait,
+    E: embedded_hal::i2c::Error,
+{
+    /// Waits for the next event.
+    ///
+    /// Reads the events whenever the device opens its communication window, and returns
+    /// the first change compared to the previous read. Changes seen in the same read are
+    /// returned by the following calls, in the order of [`Event::ALL`].
+    ///
+    /// The ready line is awaited by level, not by edge: the device releases it once the read
+    /// ends the communication window, so each read sleeps until the next window opens.
+    ///
+    /// Fails with [`Iqs231xError::DeviceReset`] if the device reset, like [`read_events`](Iqs231xDriver::read_events),
+    /// see [`acknowledge_reset`](Self::acknowledge_reset).
+    pub async fn next_event(&mut self) -> Result<Event, Iqs231xError<E>> {
+        loop {
+            if let Some(event) = self.pending.next() {
+                return Ok(event);
+            }
+
+            let events = self.driver.read_events().await?;
+            self.pending = events.changes_since(&self.previous);
+            self.previous = events;
+        }
+    }
+
+    /// Returns the events of the last read.
+    pub fn last_events(&self) -> Events {
+        self.previous
+    }
+
+    /// Acknowledges a device reset reported by [`next_event`](Self::next_event), so the stream
+    /// can resume.
+    ///
+    /// Forgets the events of the last read and any pending changes, as the device restarts
+    /// with all outputs released.
+    pub async fn acknowledge_reset(&mut self) -> Result<(), Iqs231xError<E>> {
+        self.driver.acknowledge_reset().await?;
+        self.previous = Events::default();
+        self.pending = EventChanges::default();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::asynch::Iqs231xDriver;
+    use crate::iqs231x::{AtiResult, Event, Events, InitReport, DEFAULT_ADDR};
+    use crate::registers::{
+        FilterSettings, MovementThreshold, PowerMode, ProductNumber, QuickRelease, QuickReleaseThreshold,
+    };
+    use crate::{Iqs231xConfig, Iqs231xError};
+    use alloc::vec;
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+    use embedded_hal_mock::eh1::delay;
+    use embedded_hal_mock::eh1::digital;
+    use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+
+    /// Polls a future that completes without waiting, as all futures of the I2C mock do.
+    pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("future did not complete"),
+        }
+    }
+
+    #[test]
+    fn test_product_number() {
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x00], vec![0x00, 0x40])
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        let num = block_on(sensor.product_number()).expect("Errored");
+
+        assert_eq!(num, 0x40);
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_probe_wrong_device() {
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x00], vec![0x00, 0x3C, 0x01])
+        ];
+
+        let mut mock = Mock::new(&expectations);
+
+        let result = block_on(Iqs231xDriver::probe(mock.clone()));
+        assert_eq!(result.err(), Some(Iqs231xError::WrongDevice {
+            register: 0x00,
+            product_number: ProductNumber(0x003C),
+        }));
+
+        mock.done();
+    }
+
+    #[test]
+    fn test_read_events_and_power_mode() {
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x10], vec![0b0000_1000]),
+            Transaction::write(DEFAULT_ADDR, vec![0x10, 0b0000_1010]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x00, 0b0000_0011]),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        block_on(sensor.set_power_mode(PowerMode::UltraLowPower)).expect("Errored");
+
+        let mut sensor = sensor.assume_running();
+        let events = block_on(sensor.read_events()).expect("Errored");
+        assert_eq!(events, Events { proximity: true, touch: true, ..Default::default() });
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_config_burst() {
+        let bytes = [0x0A, 0x82, 0x40, 0x18, 0x3C, 0x05, 0x84, 0x02, 0x05];
+        let mut write = vec![0x10];
+        write.extend_from_slice(&bytes);
+
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x10], bytes.to_vec()),
+            Transaction::write(DEFAULT_ADDR, write),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        let config = block_on(sensor.read_config()).expect("Errored");
+        assert_eq!(config.power.power_mode, PowerMode::UltraLowPower);
+        assert_eq!(config.prox_threshold.get(), 24);
+        assert_eq!(config.to_bytes(), bytes);
+
+        block_on(sensor.write_config(&config)).expect("Errored");
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_config_verified() {
+        let config = Iqs231xConfig::default();
+        let mut write = vec![0x10];
+        write.extend_from_slice(&config.to_bytes());
+        let mut read = config.to_bytes().to_vec();
+        read[5] |= 0xF0;
+
+        let mut corrupted = read.clone();
+        corrupted[3] = 0x00;
+
+        let expectations = [
+            Transaction::write(DEFAULT_ADDR, write.clone()),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x10], read),
+            Transaction::write(DEFAULT_ADDR, write),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x10], corrupted),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        block_on(sensor.write_config_verified(&config)).expect("Errored");
+        assert_eq!(
+            block_on(sensor.write_config_verified(&config)),
+            Err(Iqs231xError::VerifyMismatch { register: 0x13, expected: 0x04, found: 0x00 })
+        );
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_shadow_flush() {
+        let config = Iqs231xConfig::default();
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x10], config.to_bytes().to_vec()),
+            Transaction::write(DEFAULT_ADDR, vec![0x15, 0x06, 0x84]),
+            Transaction::write(DEFAULT_ADDR, vec![0x18, 0x07]),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        block_on(sensor.enable_shadow()).expect("Errored");
+
+        block_on(sensor.set_movement_threshold(MovementThreshold::new_const::<6>())).expect("Errored");
+        block_on(sensor.set_quick_release(QuickRelease {
+            threshold: QuickReleaseThreshold::new_const::<4>(),
+            enabled: true,
+        })).expect("Errored");
+        block_on(sensor.set_filter_settings(FilterSettings::from(0x07))).expect("Errored");
+        assert_eq!(block_on(sensor.movement_threshold()), Ok(MovementThreshold::new_const::<6>()));
+        assert!(sensor.is_dirty());
+
+        block_on(sensor.flush()).expect("Errored");
+        assert!(!sensor.is_dirty());
+        assert_eq!(sensor.shadow_config().expect("Disabled").filter, FilterSettings::from(0x07));
+
+        sensor.disable_shadow();
+        assert_eq!(sensor.shadow_config(), None);
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_start_waits_for_ati() {
+        let config = Iqs231xConfig::default();
+        let mut write = vec![0x10];
+        write.extend_from_slice(&config.to_bytes());
+
+        let expectations = [
+            Transaction::write(DEFAULT_ADDR, write),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0x01]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x01]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x00]),
+        ];
+
+        let delays = [
+            delay::Transaction::async_delay_us(10_000),
+        ];
+
+        let mock = Mock::new(&expectations);
+        let mut delay = delay::CheckedDelay::new(&delays);
+
+        let sensor = block_on(Iqs231xDriver::new(mock).configure(&config)).map_err(|(_, e)| e).expect("Errored");
+        let sensor = block_on(sensor.start(&mut delay)).map_err(|(_, e)| e).expect("Errored");
+
+        sensor.release_inner().done();
+        delay.done();
+    }
+
+    #[test]
+    fn test_init() {
+        let config = Iqs231xConfig::default();
+        let mut write = vec![0x10];
+        write.extend_from_slice(&config.to_bytes());
+
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x80]),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0x04]),
+            Transaction::write(DEFAULT_ADDR, write),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x10], config.to_bytes().to_vec()),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0x01]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x01]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x01]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x00]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x0B], vec![0b0001_0011, 0x80]),
+        ];
+        let delays = [
+            delay::Transaction::async_delay_us(20_000),
+            delay::Transaction::async_delay_us(10_000),
+            delay::Transaction::async_delay_us(10_000),
+        ];
+
+        let mock = Mock::new(&expectations);
+        let mut delay = delay::CheckedDelay::new(&delays);
+
+        let (sensor, report) = block_on(Iqs231xDriver::new(mock).init(&mut delay, &config))
+            .map_err(|(_, e)| e)
+            .expect("Errored");
+
+        assert_eq!(report, InitReport {
+            reset_acknowledged: true,
+            ati: AtiResult { sensitivity_multiplier: 3, compensation_multiplier: 1, compensation: 0x80 },
+            ati_time_us: 20_000,
+        });
+
+        sensor.release_inner().done();
+        delay.done();
+    }
+
+    #[test]
+    fn test_program_address() {
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x20], vec![0x04]),
+            Transaction::write(DEFAULT_ADDR, vec![0x20, 0x05]),
+            Transaction::write_read(0x45, vec![0x00], vec![0x00, 0x40, 0x06]),
+            Transaction::write_read(0x45, vec![0x20], vec![0x05]),
+            Transaction::write(0x45, vec![0x20, 0x06]),
+            Transaction::write_read(0x46, vec![0x00], vec![0x00, 0x3C, 0x01]),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        block_on(sensor.program_address(0x45)).expect("Errored");
+        assert_eq!(sensor.address(), 0x45);
+
+        assert_eq!(
+            block_on(sensor.program_address(0x46)),
+            Err(Iqs231xError::WrongDevice { register: 0x00, product_number: ProductNumber(0x003C) })
+        );
+        assert_eq!(sensor.address(), 0x45);
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_read_otp() {
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x20], vec![0x08, 24, 60, 0x35]),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        let otp = block_on(sensor.read_otp()).expect("Errored").decode().expect("Invalid");
+        assert_eq!(otp.address, DEFAULT_ADDR);
+        assert_eq!(otp.touch_threshold.get(), 60);
+
+        sensor.release_inner().done();
+    }
+
+    #[cfg(feature = "otp-write")]
+    #[test]
+    fn test_program_otp() {
+        use crate::otp::{OtpImage, OtpWriteToken};
+
+        let image = OtpImage([0x0B, 24, 60, 0x35]);
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x20], vec![0x08, 24, 60, 0x35]),
+            Transaction::write(DEFAULT_ADDR, vec![0x20, 0x0B, 24, 60, 0x35]),
+            Transaction::write_read(0x47, vec![0x00], vec![0x00, 0x40, 0x06]),
+            Transaction::write(0x47, vec![0x04, 0x40]),
+            Transaction::write(0x47, vec![0x04, 0x80]),
+            Transaction::write_read(0x47, vec![0x00], vec![0x00, 0x40, 0x06]),
+            Transaction::write_read(0x47, vec![0x20], vec![0x0B, 24, 60, 0x00]),
+        ];
+        let delays = [
+            delay::Transaction::async_delay_us(50_000),
+            delay::Transaction::async_delay_us(20_000),
+        ];
+
+        let mock = Mock::new(&expectations);
+        let mut delay = delay::CheckedDelay::new(&delays);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        assert_eq!(
+            block_on(sensor.program_otp(&mut delay, OtpWriteToken::confirm_permanent_write(image))),
+            Err(Iqs231xError::VerifyMismatch { register: 0x23, expected: 0x35, found: 0x00 })
+        );
+        assert_eq!(sensor.address(), 0x47);
+
+        sensor.release_inner().done();
+        delay.done();
+    }
+
+    #[test]
+    fn test_ready_pin() {
+        let expectations = [
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0x01]),
+        ];
+        let pin_expectations = [
+            digital::Transaction::wait_for_state(digital::State::Low),
+        ];
+
+        let mock = Mock::new(&expectations);
+        let mut pin = digital::Mock::new(&pin_expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock).with_ready_pin(pin.clone()).assume_running();
+        block_on(sensor.redo_ati()).expect("Errored");
+
+        sensor.release_inner().done();
+        pin.done();
+    }
+
+    #[test]
+    fn test_event_stream() {
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x00, 0b0000_0000]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x00, 0b0000_0011]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x00, 0b0000_1000]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x80, 0b0000_0000]),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0x04]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x00, 0b0000_1000]),
+        ];
+        let pin_expectations = [
+            digital::Transaction::wait_for_state(digital::State::Low),
+            digital::Transaction::wait_for_state(digital::State::Low),
+            digital::Transaction::wait_for_state(digital::State::Low),
+            digital::Transaction::wait_for_state(digital::State::Low),
+            digital::Transaction::wait_for_state(digital::State::Low),
+            digital::Transaction::wait_for_state(digital::State::Low),
+        ];
+
+        let mock = Mock::new(&expectations);
+        let mut pin = digital::Mock::new(&pin_expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock).with_ready_pin(pin.clone()).assume_running();
+        let mut stream = sensor.event_stream();
+
+        assert_eq!(block_on(stream.next_event()), Ok(Event::ProxEnter));
+        assert_eq!(block_on(stream.next_event()), Ok(Event::TouchDown));
+        assert_eq!(block_on(stream.next_event()), Ok(Event::TouchUp));
+        assert_eq!(block_on(stream.next_event()), Ok(Event::QuickRelease));
+        assert_eq!(block_on(stream.next_event()), Ok(Event::ProxExit));
+        assert_eq!(block_on(stream.next_event()), Err(Iqs231xError::DeviceReset { register: 0x05 }));
+
+        block_on(stream.acknowledge_reset()).expect("Errored");
+        assert_eq!(stream.last_events(), Events::default());
+        assert_eq!(block_on(stream.next_event()), Ok(Event::QuickRelease));
+
+        sensor.release_inner().done();
+        pin.done();
+    }
+}
@@ -0,0 +1,274 @@
+//! Whole-device configuration.
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+use core::ops::Range;
+
+use crate::iqs231x::AtiConfig;
+use crate::registers::{
+    AtiSettings, AtiTarget, FilterSettings, HaltTime, InvalidValue, MovementThreshold, PowerSettings, ProxThreshold, QuickRelease,
+    QuickReleaseThreshold, Register, TouchThreshold, CONFIG_LEN, CONFIG_START,
+};
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::Iqs231xError;
+
+/// Bits of each configuration register compared by
+/// [`write_config_verified`](crate::Iqs231xDriver::write_config_verified).
+///
+/// Reserved bits read back undefined values and are left out.
+pub const CONFIG_VERIFY_MASK: [u8; CONFIG_LEN] = [
+    0x1F, // power settings
+    0x83, // ATI settings
+    0xFF, // ATI target
+    0xFF, // proximity threshold
+    0xFF, // touch threshold
+    0x0F, // movement threshold
+    0x8F, // quick release
+    0x07, // halt time
+    0x1F, // filter settings
+];
+
+/// Every writable setting of the device, covering the configuration block
+/// ([`CONFIG_START`], [`CONFIG_LEN`] registers long).
+///
+/// Read and written in one transaction by [`read_config`](crate::Iqs231xDriver::read_config)
+/// and [`write_config`](crate::Iqs231xDriver::write_config).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Iqs231xConfig {
+    pub power: PowerSettings,
+    pub ati: AtiConfig,
+    pub prox_threshold: ProxThreshold,
+    pub touch_threshold: TouchThreshold,
+    pub movement_threshold: MovementThreshold,
+    pub quick_release: QuickRelease,
+    pub halt_time: HaltTime,
+    pub filter: FilterSettings,
+}
+
+impl Default for Iqs231xConfig {
+    /// Normal power mode, full ATI towards 512 counts, a proximity threshold of 4,
+    /// a touch threshold of 32, a movement threshold of 4 and quick release disabled.
+    fn default() -> Self {
+        Self {
+            power: PowerSettings::default(),
+            ati: AtiConfig {
+                settings: AtiSettings::default(),
+                target: AtiTarget(64),
+            },
+            prox_threshold: ProxThreshold::new_const::<4>(),
+            touch_threshold: TouchThreshold::new_const::<32>(),
+            movement_threshold: MovementThreshold::new_const::<4>(),
+            quick_release: QuickRelease {
+                threshold: QuickReleaseThreshold::new_const::<4>(),
+                enabled: false,
+            },
+            halt_time: HaltTime::default(),
+            filter: FilterSettings::default(),
+        }
+    }
+}
+
+impl Iqs231xConfig {
+    /// Decodes the configuration from the contents of the configuration block.
+    ///
+    /// Fails with the first register holding a value outside the range of its setting.
+    pub fn from_bytes(bytes: [u8; CONFIG_LEN]) -> Result<Self, InvalidValue> {
+        Ok(Self {
+            power: field(&bytes)?,
+            ati: AtiConfig {
+                settings: field(&bytes)?,
+                target: field(&bytes)?,
+            },
+            prox_threshold: field(&bytes)?,
+            touch_threshold: field(&bytes)?,
+            movement_threshold: field(&bytes)?,
+            quick_release: field(&bytes)?,
+            halt_time: field(&bytes)?,
+            filter: field(&bytes)?,
+        })
+    }
+
+    /// Encodes the configuration into the contents of the configuration block.
+    pub fn to_bytes(&self) -> [u8; CONFIG_LEN] {
+        let mut bytes = [0; CONFIG_LEN];
+
+        set_field(&mut bytes, self.power);
+        set_field(&mut bytes, self.ati.settings);
+        set_field(&mut bytes, self.ati.target);
+        set_field(&mut bytes, self.prox_threshold);
+        set_field(&mut bytes, self.touch_threshold);
+        set_field(&mut bytes, self.movement_threshold);
+        set_field(&mut bytes, self.quick_release);
+        set_field(&mut bytes, self.halt_time);
+        set_field(&mut bytes, self.filter);
+
+        bytes
+    }
+
+    /// Compares the configuration against the contents read back from the
+    /// configuration block, ignoring the bits outside [`CONFIG_VERIFY_MASK`].
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn verify<E>(&self, found: &[u8; CONFIG_LEN]) -> Result<(), Iqs231xError<E>> {
+        let expected = self.to_bytes();
+
+        for (offset, mask) in CONFIG_VERIFY_MASK.iter().enumerate() {
+            if (expected[offset] ^ found[offset]) & mask != 0 {
+                return Err(Iqs231xError::VerifyMismatch {
+                    register: CONFIG_START + offset as u8,
+                    expected: expected[offset],
+                    found: found[offset],
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Driver-side copy of the configuration block, with the contents last written to
+/// or read from the device to tell which registers changed.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub(crate) struct ConfigShadow {
+    bytes: [u8; CONFIG_LEN],
+    synced: [u8; CONFIG_LEN],
+}
+
+impl ConfigShadow {
+    pub(crate) fn bytes(&self) -> &[u8; CONFIG_LEN] {
+        &self.bytes
+    }
+
+    pub(crate) fn is_dirty(&self) -> bool {
+        self.bytes != self.synced
+    }
+}
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+impl ConfigShadow {
+    /// Creates a shadow in sync with the device contents `bytes`.
+    pub(crate) fn new(bytes: [u8; CONFIG_LEN]) -> Self {
+        Self { bytes, synced: bytes }
+    }
+
+    /// Returns the offsets of `len` registers from `register`, if they are all in the configuration block.
+    fn range(register: u8, len: usize) -> Option<Range<usize>> {
+        let start = register.checked_sub(CONFIG_START)? as usize;
+
+        (start + len <= CONFIG_LEN).then_some(start..start + len)
+    }
+
+    /// Copies the cached registers from `register` into `buf`, returning `false` if they are not cached.
+    pub(crate) fn read(&self, register: u8, buf: &mut [u8]) -> bool {
+        let Some(range) = Self::range(register, buf.len()) else {
+            return false;
+        };
+
+        buf.copy_from_slice(&self.bytes[range]);
+        true
+    }
+
+    /// Updates the cached registers from `register`, returning `false` if they are not cached.
+    pub(crate) fn write(&mut self, register: u8, payload: &[u8]) -> bool {
+        let Some(range) = Self::range(register, payload.len()) else {
+            return false;
+        };
+
+        self.bytes[range].copy_from_slice(payload);
+        true
+    }
+
+    /// Returns the offsets of the first run of consecutive changed registers.
+    pub(crate) fn next_dirty(&self) -> Option<Range<usize>> {
+        let differs = |offset: &usize| self.bytes[*offset] != self.synced[*offset];
+        let start = (0..CONFIG_LEN).find(differs)?;
+        let end = (start..CONFIG_LEN).find(|offset| !differs(offset)).unwrap_or(CONFIG_LEN);
+
+        Some(start..end)
+    }
+
+    /// Records that the registers at `range` were written to the device.
+    pub(crate) fn mark_synced(&mut self, range: Range<usize>) {
+        self.synced[range.clone()].copy_from_slice(&self.bytes[range]);
+    }
+}
+
+fn field<R: Register>(bytes: &[u8; CONFIG_LEN]) -> Result<R, InvalidValue> {
+    R::from_raw(bytes[(R::ADDRESS - CONFIG_START) as usize])
+}
+
+fn set_field<R: Register>(bytes: &mut [u8; CONFIG_LEN], value: R) {
+    bytes[(R::ADDRESS - CONFIG_START) as usize] = value.into_raw();
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    use crate::config::ConfigShadow;
+    use crate::config::Iqs231xConfig;
+    use crate::registers::{HaltTime, InvalidValue, PowerMode, ReportRate};
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    use crate::Iqs231xError;
+
+    #[test]
+    fn test_round_trip() {
+        let mut config = Iqs231xConfig::default();
+        config.power.power_mode = PowerMode::UltraLowPower;
+        config.power.report_rate = ReportRate::Ms128;
+        config.halt_time = HaltTime::Infinite;
+        config.quick_release.enabled = true;
+
+        let bytes = config.to_bytes();
+        assert_eq!(bytes, [0x12, 0x01, 0x40, 0x04, 0x20, 0x04, 0x84, 0x07, 0x05]);
+        assert_eq!(Iqs231xConfig::from_bytes(bytes), Ok(config));
+
+        let mut blank = bytes;
+        blank[3] = 0x00;
+        assert_eq!(Iqs231xConfig::from_bytes(blank), Err(InvalidValue { register: 0x13, value: 0 }));
+    }
+
+    #[test]
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn test_verify_masks_reserved_bits() {
+        let config = Iqs231xConfig::default();
+
+        let mut found = config.to_bytes();
+        found[0] |= 0xE0;
+        found[5] |= 0xF0;
+        assert_eq!(config.verify::<()>(&found), Ok(()));
+
+        found[3] ^= 0x01;
+        assert_eq!(
+            config.verify::<()>(&found),
+            Err(Iqs231xError::VerifyMismatch {
+                register: 0x13,
+                expected: 0x04,
+                found: 0x05,
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn test_shadow_dirty_runs() {
+        let mut shadow = ConfigShadow::new(Iqs231xConfig::default().to_bytes());
+        assert!(!shadow.is_dirty());
+        assert!(!shadow.write(0x19, &[0x00]));
+        assert!(!shadow.write(0x17, &[0x00, 0x00, 0x00]));
+
+        assert!(shadow.write(0x13, &[0x05, 0x21]));
+        assert!(shadow.write(0x18, &[0x00]));
+        assert!(shadow.is_dirty());
+        assert_eq!(shadow.next_dirty(), Some(3..5));
+
+        shadow.mark_synced(3..5);
+        assert_eq!(shadow.next_dirty(), Some(8..9));
+
+        shadow.mark_synced(8..9);
+        assert!(!shadow.is_dirty());
+
+        let mut buf = [0; 2];
+        assert!(shadow.read(0x13, &mut buf));
+        assert_eq!(buf, [0x05, 0x21]);
+    }
+}
@@ -1,12 +1,154 @@
+use crate::registers::{InvalidValue, ProductNumber};
+use core::fmt;
+use embedded_hal::i2c::ErrorKind;
 
 #[derive(Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub enum Iqs231xError<E> {
-    I2CError(E)
+    /// The I2C transaction failed.
+    I2CError(E),
+    /// The device at the address reported a product number other than [`ProductNumber::IQS231`].
+    WrongDevice {
+        register: u8,
+        product_number: ProductNumber,
+    },
+    /// A configuration value is outside the valid range of its register.
+    InvalidValue(InvalidValue),
+    /// The ATI could not reach its target, as reported by the system flags.
+    AtiFailed {
+        register: u8,
+    },
+    /// The device reset while running, as reported by the system flags.
+    ///
+    /// The device restarted from its OTP settings, acknowledge the reset and configure it again.
+    DeviceReset {
+        register: u8,
+    },
+    /// The device did not become ready in time.
+    Timeout {
+        register: u8,
+    },
+    /// The ready line could not be read.
+    ReadyPin {
+        register: u8,
+        kind: embedded_hal::digital::ErrorKind,
+    },
+    /// A register read back a value other than the one written to it.
+    VerifyMismatch {
+        register: u8,
+        expected: u8,
+        found: u8,
+    },
+}
+
+impl<E> Iqs231xError<E> {
+    /// Returns the register involved in the error, if known.
+    pub fn register(&self) -> Option<u8> {
+        match self {
+            Iqs231xError::I2CError(_) => None,
+            Iqs231xError::InvalidValue(invalid) => Some(invalid.register),
+            Iqs231xError::WrongDevice { register, .. }
+            | Iqs231xError::AtiFailed { register }
+            | Iqs231xError::DeviceReset { register }
+            | Iqs231xError::Timeout { register }
+            | Iqs231xError::ReadyPin { register, .. }
+            | Iqs231xError::VerifyMismatch { register, .. } => Some(*register),
+        }
+    }
 }
 
 impl<E> From<E> for Iqs231xError<E> {
     fn from(value: E) -> Self {
         Iqs231xError::I2CError(value)
     }
-}
\ No newline at end of file
+}
+
+impl<E: fmt::Debug> fmt::Display for Iqs231xError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Iqs231xError::I2CError(e) => write!(f, "I2C error: {:?}", e),
+            Iqs231xError::WrongDevice { register, product_number } => {
+                write!(f, "wrong device, register {:#04x} reads product number {:#06x}", register, product_number.0)
+            }
+            Iqs231xError::InvalidValue(invalid) => write!(f, "{}", invalid),
+            Iqs231xError::AtiFailed { register } => write!(f, "ATI failed, reported by register {:#04x}", register),
+            Iqs231xError::DeviceReset { register } => write!(f, "device reset, reported by register {:#04x}", register),
+            Iqs231xError::Timeout { register } => write!(f, "timeout waiting for register {:#04x}", register),
+            Iqs231xError::ReadyPin { register, kind } => {
+                write!(f, "ready line error while accessing register {:#04x}: {:?}", register, kind)
+            }
+            Iqs231xError::VerifyMismatch { register, expected, found } => write!(
+                f,
+                "register {:#04x} reads {:#04x}, expected {:#04x}",
+                register, found, expected
+            ),
+        }
+    }
+}
+
+impl<E: fmt::Debug> core::error::Error for Iqs231xError<E> {}
+
+impl<E: embedded_hal::i2c::Error> embedded_hal::i2c::Error for Iqs231xError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Iqs231xError::I2CError(e) => e.kind(),
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+/// Errors of the single-wire backend, see [`single_wire`](crate::single_wire).
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum SingleWireError<E> {
+    /// The IO line could not be read.
+    Pin(E),
+    /// No frame started in time.
+    Timeout,
+    /// The start or stop bit of a frame had the wrong level.
+    Framing,
+    /// The checksum of a frame does not match its contents.
+    Checksum {
+        expected: u8,
+        found: u8,
+    },
+}
+
+impl<E: fmt::Debug> fmt::Display for SingleWireError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SingleWireError::Pin(e) => write!(f, "IO line error: {:?}", e),
+            SingleWireError::Timeout => write!(f, "timeout waiting for a frame"),
+            SingleWireError::Framing => write!(f, "framing error"),
+            SingleWireError::Checksum { expected, found } => {
+                write!(f, "frame checksum is {:#04x}, expected {:#04x}", found, expected)
+            }
+        }
+    }
+}
+
+impl<E: fmt::Debug> core::error::Error for SingleWireError<E> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::registers::{InvalidValue, PROX_THRESHOLD};
+    use crate::Iqs231xError;
+    use alloc::string::ToString;
+    use embedded_hal::i2c::{Error, ErrorKind, NoAcknowledgeSource};
+
+    #[test]
+    fn test_error_kind_and_register() {
+        let nack: Iqs231xError<ErrorKind> = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address).into();
+        assert_eq!(nack.kind(), ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        assert_eq!(nack.register(), None);
+
+        let mismatch: Iqs231xError<ErrorKind> = Iqs231xError::VerifyMismatch { register: 0x13, expected: 0x10, found: 0x11 };
+        assert_eq!(mismatch.kind(), ErrorKind::Other);
+        assert_eq!(mismatch.register(), Some(0x13));
+        assert_eq!(mismatch.to_string(), "register 0x13 reads 0x11, expected 0x10");
+
+        let invalid: Iqs231xError<ErrorKind> = Iqs231xError::InvalidValue(InvalidValue { register: PROX_THRESHOLD, value: 0 });
+        assert_eq!(invalid.register(), Some(PROX_THRESHOLD));
+        assert_eq!(invalid.to_string(), "value 0 is out of range for register 0x13");
+    }
+}
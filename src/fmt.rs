@@ -0,0 +1,16 @@
+//! Logging macros, forwarding to `defmt` or `log` depending on the enabled features.
+//!
+//! The format strings are limited to the syntax shared by both crates (`{}`, `{:?}`, `{:#04x}`).
+
+macro_rules! trace {
+    ($s:literal $(, $x:expr)* $(,)?) => {
+        {
+            #[cfg(feature = "defmt-03")]
+            defmt::trace!($s $(, $x)*);
+            #[cfg(feature = "log")]
+            ::log::trace!($s $(, $x)*);
+            #[cfg(not(any(feature = "defmt-03", feature = "log")))]
+            let _ = ($( & $x ),*);
+        }
+    };
+}
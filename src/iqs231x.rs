@@ -1,18 +1,355 @@
+use core::marker::PhantomData;
+use core::ops::RangeInclusive;
+
+use crate::config::{ConfigShadow, Iqs231xConfig};
+use crate::ready::{NoPin, DEFAULT_READY_TIMEOUT_US};
+#[cfg(feature = "blocking")]
+use crate::ready::READY_POLL_INTERVAL_US;
+use crate::retry::{NoDelay, RetryPolicy};
+use crate::state::{Configured, Running, Uninitialized};
+#[cfg(feature = "blocking")]
+use crate::state::{Configurable, Transition, ATI_POLL_INTERVAL_US, ATI_TIMEOUT_US, POWER_UP_TIME_US};
+use crate::registers::{AtiSettings, AtiTarget, Counts, Lta, MainEvents, ProductNumber, SoftwareNumber, SystemFlags};
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::registers::{AtiMultipliers, Commands};
+#[cfg(feature = "blocking")]
+use crate::registers::{
+    DebugEvents, FilterSettings, HaltTime, MovementThreshold, PowerMode, PowerSettings, ProxThreshold, QuickRelease,
+    Register, ReportRate, TouchThreshold, ATI_MULTIPLIERS, ATI_SETTINGS, COUNTS, LTA, PRODUCT_NUMBER, SYSTEM_FLAGS,
+};
+#[cfg(feature = "blocking")]
+use crate::registers::{InvalidValue, OtpShadow, CONFIG_LEN, CONFIG_START, OTP_BANK_COUNT, OTP_SHADOW_0};
+#[cfg(feature = "blocking")]
+use crate::otp::OtpImage;
+#[cfg(all(feature = "blocking", feature = "otp-write"))]
+use crate::otp::{self, OtpWriteToken, OTP_PROGRAM_TIME_US};
+#[cfg(all(feature = "blocking", feature = "otp-write"))]
+use crate::registers::COMMANDS;
+#[cfg(feature = "blocking")]
 use crate::Iqs231xError;
 use embedded_hal::i2c::SevenBitAddress;
 
 /// The default address of the IQS231A/B chips on I2C
 pub const DEFAULT_ADDR: SevenBitAddress = 0x44;
 
+/// The addresses an IQS231A/B can be moved to, see [`program_address`](Iqs231xDriver::program_address).
+pub const ADDR_RANGE: RangeInclusive<SevenBitAddress> = DEFAULT_ADDR..=0x47;
+
+/// Largest number of registers written in one transaction.
+#[cfg(any(feature = "blocking", feature = "async"))]
+pub(crate) const MAX_WRITE_LEN: usize = 16;
+
+
+/// The variant of the IQS231 family.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Variant {
+    Iqs231A,
+    Iqs231B,
+}
+
+/// Identification of the device, as returned by [`device_info`](Iqs231xDriver::device_info).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct DeviceInfo {
+    pub product_number: ProductNumber,
+    pub software_number: SoftwareNumber,
+}
+
+impl DeviceInfo {
+    /// Returns `true` if the product number belongs to the IQS231 family.
+    pub fn is_iqs231(&self) -> bool {
+        self.product_number == ProductNumber::IQS231
+    }
+
+    /// Returns the variant of the device, or `None` if it is not an IQS231
+    /// or runs an unknown software version.
+    pub fn variant(&self) -> Option<Variant> {
+        if !self.is_iqs231() {
+            return None;
+        }
+
+        match self.software_number {
+            SoftwareNumber::IQS231A => Some(Variant::Iqs231A),
+            SoftwareNumber::IQS231B => Some(Variant::Iqs231B),
+            _ => None,
+        }
+    }
+
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn from_bytes(bytes: [u8; 3]) -> Self {
+        Self {
+            product_number: ProductNumber::from_be_bytes([bytes[0], bytes[1]]),
+            software_number: SoftwareNumber(bytes[2]),
+        }
+    }
+}
+
+/// Sensing outputs and state of the device, as returned by [`read_events`](Iqs231xDriver::read_events).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Events {
+    pub proximity: bool,
+    pub touch: bool,
+    pub movement: bool,
+    /// The last proximity was released by the quick-release detection, see
+    /// [`set_quick_release`](Iqs231xDriver::set_quick_release).
+    pub quick_release: bool,
+    pub ati_busy: bool,
+    pub ati_error: bool,
+    /// The device has reset since the reset was last acknowledged.
+    ///
+    /// Only meaningful for events read with the [`single_wire`](crate::single_wire) interface.
+    /// Always `false` when read over I2C, as
+    /// [`read_events`](Iqs231xDriver::read_events) fails with [`DeviceReset`](crate::Iqs231xError::DeviceReset) instead.
+    pub device_reset: bool,
+}
+
+impl Events {
+    pub(crate) fn from_bytes(bytes: [u8; 2]) -> Self {
+        let flags = SystemFlags::from(bytes[0]);
+        let events = MainEvents::from(bytes[1]);
+
+        Self {
+            proximity: events.proximity,
+            touch: events.touch,
+            movement: events.movement,
+            quick_release: events.quick_release,
+            ati_busy: flags.ati_busy,
+            ati_error: flags.ati_error,
+            device_reset: flags.show_reset,
+        }
+    }
+
+    /// Returns the events that occurred between `previous` and `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::iqs231x::{Event, Events};
+    ///
+    /// let previous = Events { proximity: true, ..Default::default() };
+    /// let current = Events { proximity: true, touch: true, ..Default::default() };
+    ///
+    /// assert!(current.changes_since(&previous).eq([Event::TouchDown]));
+    /// ```
+    pub fn changes_since(&self, previous: &Events) -> EventChanges {
+        let rising = |now: bool, before: bool| now && !before;
+        let changes = [
+            rising(self.proximity, previous.proximity),
+            rising(self.touch, previous.touch),
+            rising(self.movement, previous.movement),
+            rising(previous.touch, self.touch),
+            rising(self.quick_release, previous.quick_release),
+            rising(previous.proximity, self.proximity),
+        ];
+
+        let mut pending = 0;
+        for (i, changed) in changes.into_iter().enumerate() {
+            if changed {
+                pending |= 1 << i;
+            }
+        }
+
+        EventChanges { pending }
+    }
+}
+
+/// A change of the sensing outputs of the device.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Event {
+    /// A proximity was detected.
+    ProxEnter,
+    /// A touch was detected.
+    TouchDown,
+    /// Movement was detected.
+    Movement,
+    /// The touch was released.
+    TouchUp,
+    /// The proximity was released by the quick-release detection.
+    QuickRelease,
+    /// The proximity was released.
+    ProxExit,
+}
+
+impl Event {
+    /// Every event, in the order [`EventChanges`] yields them.
+    pub const ALL: [Event; 6] = [
+        Event::ProxEnter,
+        Event::TouchDown,
+        Event::Movement,
+        Event::TouchUp,
+        Event::QuickRelease,
+        Event::ProxExit,
+    ];
+}
+
+/// Iterator over the events between two readouts, see [`Events::changes_since`].
+///
+/// Events are yielded in the order of [`Event::ALL`].
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct EventChanges {
+    pending: u8,
+}
+
+impl Iterator for EventChanges {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        if self.pending == 0 {
+            return None;
+        }
+
+        let index = self.pending.trailing_zeros();
+        self.pending &= !(1 << index);
+
+        Some(Event::ALL[index as usize])
+    }
+}
+
+/// Difference between the long-term average and the channel counts.
+///
+/// The counts of the IQS231 drop as the capacitance of the pad rises, so the delta
+/// is positive when an object approaches the pad.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Delta(pub i16);
+
+impl Delta {
+    /// Computes the delta from the LTA and the counts, saturating at the bounds of `i16`.
+    pub fn new(counts: Counts, lta: Lta) -> Self {
+        let delta = i32::from(lta.0) - i32::from(counts.0);
+
+        Self(delta.clamp(i16::MIN.into(), i16::MAX.into()) as i16)
+    }
+}
+
+/// A consistent sample of the channel, as returned by [`read_channel`](Iqs231xDriver::read_channel).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ChannelData {
+    pub counts: Counts,
+    pub lta: Lta,
+    pub delta: Delta,
+}
+
+impl ChannelData {
+    pub(crate) fn from_bytes(bytes: [u8; 4]) -> Self {
+        let counts = Counts::from_be_bytes([bytes[0], bytes[1]]);
+        let lta = Lta::from_be_bytes([bytes[2], bytes[3]]);
+
+        Self {
+            counts,
+            lta,
+            delta: Delta::new(counts, lta),
+        }
+    }
+}
+
+/// Communication mode of the device.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum CommunicationMode {
+    /// The device only opens a communication window when an event occurs.
+    Event,
+    /// The device opens a communication window every cycle.
+    Streaming,
+}
+
+impl CommunicationMode {
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn command(self) -> Commands {
+        match self {
+            CommunicationMode::Event => Commands { event_mode: true, ..Default::default() },
+            CommunicationMode::Streaming => Commands { streaming_mode: true, ..Default::default() },
+        }
+    }
+}
+
+/// ATI configuration, as used by [`set_ati_config`](Iqs231xDriver::set_ati_config).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct AtiConfig {
+    pub settings: AtiSettings,
+    pub target: AtiTarget,
+}
+
+impl AtiConfig {
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn from_bytes(bytes: [u8; 2]) -> Self {
+        Self {
+            settings: AtiSettings::from(bytes[0]),
+            target: AtiTarget::from(bytes[1]),
+        }
+    }
+
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn to_bytes(self) -> [u8; 2] {
+        [self.settings.into(), self.target.into()]
+    }
+}
+
+/// Multipliers and compensation selected by the last ATI, as returned by
+/// [`ati_result`](Iqs231xDriver::ati_result).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct AtiResult {
+    pub sensitivity_multiplier: u8,
+    pub compensation_multiplier: u8,
+    /// ATI compensation, 10 bits.
+    pub compensation: u16,
+}
+
+impl AtiResult {
+    /// Largest compensation the ATI can select.
+    pub const MAX_COMPENSATION: u16 = 0x3FF;
+
+    /// Returns `true` if the compensation ended at one of its limits, meaning the ATI
+    /// could not settle inside its working range and the pad design should be checked.
+    pub fn is_at_limit(&self) -> bool {
+        self.compensation == 0 || self.compensation == Self::MAX_COMPENSATION
+    }
+
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn from_bytes(bytes: [u8; 2]) -> Self {
+        let multipliers = AtiMultipliers::from(bytes[0]);
+
+        Self {
+            sensitivity_multiplier: multipliers.sensitivity,
+            compensation_multiplier: multipliers.compensation,
+            compensation: u16::from(multipliers.compensation_high) << 8 | u16::from(bytes[1]),
+        }
+    }
+}
 
-// register addrs:
-const PRODUCT_NUMBER_REG: u8 = 0x00;
+/// Outcome of the start-up sequence run by [`init`](Iqs231xDriver::init).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct InitReport {
+    /// The device reported a reset, which was acknowledged.
+    pub reset_acknowledged: bool,
+    /// Multipliers and compensation selected by the ATI.
+    pub ati: AtiResult,
+    /// Time the ATI took, in microseconds, rounded up to the poll interval.
+    pub ati_time_us: u32,
+}
+
+/// A running driver returned by [`init`](Iqs231xDriver::init), with the report of the start-up sequence.
+pub type Initialized<I2C, D, RDY> = (Iqs231xDriver<I2C, D, RDY, Running>, InitReport);
 
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
-pub struct Iqs231xDriver<I2C> {
+pub struct Iqs231xDriver<I2C, D = NoDelay, RDY = NoPin, S = Uninitialized> {
     address: SevenBitAddress,
-    i2c: I2C
+    i2c: I2C,
+    delay: D,
+    retry: RetryPolicy,
+    ready: RDY,
+    ready_timeout_us: u32,
+    shadow: Option<ConfigShadow>,
+    state: PhantomData<S>,
 }
 
 #[warn(missing_docs)]
@@ -32,10 +369,7 @@ impl <I2C> Iqs231xDriver<I2C> {
     /// # sensor.release_inner().done();
     /// ```
     pub fn new(i2c: I2C) -> Self {
-        Self {
-            address: DEFAULT_ADDR,
-            i2c
-        }
+        Self::with_address(i2c, DEFAULT_ADDR)
     }
 
     /// Creates a new driver instance with a custom I2C address.
@@ -61,11 +395,101 @@ impl <I2C> Iqs231xDriver<I2C> {
         Self {
             address: addr,
             i2c,
+            delay: NoDelay,
+            retry: RetryPolicy::NONE,
+            ready: NoPin,
+            ready_timeout_us: DEFAULT_READY_TIMEOUT_US,
+            shadow: None,
+            state: PhantomData,
+        }
+    }
+}
+
+#[warn(missing_docs)]
+impl<I2C, D, RDY, S> Iqs231xDriver<I2C, D, RDY, S> {
+    /// Retries NACKed transactions according to `policy`, waiting with `delay` between attempts.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::retry::RetryPolicy;
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # let i2c_interface = embedded_hal_mock::eh1::i2c::Mock::new(&[]);
+    /// # let delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    ///
+    /// let sensor = Iqs231xDriver::new(i2c_interface).with_retry(delay, RetryPolicy::new(5, 500));
+    /// # sensor.release_inner().done();
+    /// ```
+    pub fn with_retry<D2>(self, delay: D2, policy: RetryPolicy) -> Iqs231xDriver<I2C, D2, RDY, S> {
+        Iqs231xDriver {
+            address: self.address,
+            i2c: self.i2c,
+            delay,
+            retry: policy,
+            ready: self.ready,
+            ready_timeout_us: self.ready_timeout_us,
+            shadow: self.shadow,
+            state: self.state,
+        }
+    }
+
+    /// Waits for the communication window on the device's ready (IO) line before every transaction.
+    ///
+    /// The line is active low. The blocking driver polls it every [`READY_POLL_INTERVAL_US`]
+    /// with `delay`, giving up after the ready timeout. `delay` replaces the delay given to
+    /// [`with_retry`](Self::with_retry), the retry policy is kept.
+    ///
+    /// [`READY_POLL_INTERVAL_US`]: crate::ready::READY_POLL_INTERVAL_US
+    pub fn with_ready_pin<P, D2>(self, pin: P, delay: D2) -> Iqs231xDriver<I2C, D2, P, S> {
+        Iqs231xDriver {
+            address: self.address,
+            i2c: self.i2c,
+            delay,
+            retry: self.retry,
+            ready: pin,
+            ready_timeout_us: self.ready_timeout_us,
+            shadow: self.shadow,
+            state: self.state,
         }
     }
 
+    /// Updates how long the blocking driver waits for the communication window, in microseconds.
+    pub fn set_ready_timeout_us(&mut self, timeout_us: u32) {
+        self.ready_timeout_us = timeout_us;
+    }
+
+    /// Updates the retry policy, keeping the delay.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry = policy;
+    }
+
+    /// Returns the current retry policy.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry
+    }
+
+    /// Returns the cached configuration, if the shadow is enabled.
+    ///
+    /// Contains the changes not yet written by [`flush`](Self::flush).
+    pub fn shadow_config(&self) -> Option<Iqs231xConfig> {
+        // The shadow only holds contents validated by `enable_shadow` and the setters.
+        self.shadow.and_then(|shadow| Iqs231xConfig::from_bytes(*shadow.bytes()).ok())
+    }
+
+    /// Returns `true` if the shadow holds changes not yet written by [`flush`](Self::flush).
+    pub fn is_dirty(&self) -> bool {
+        self.shadow.is_some_and(|shadow| shadow.is_dirty())
+    }
+
+    /// Stops caching the configuration registers, discarding any changes not yet flushed.
+    pub fn disable_shadow(&mut self) {
+        self.shadow = None;
+    }
+
     /// Updates the device's I2C address.
     ///
+    /// Only the driver's copy changes, use [`program_address`](Self::program_address) to move the device.
+    ///
     /// # Arguments
     ///
     /// - `addr` - New 7-bit I2C address
@@ -128,57 +552,1626 @@ impl <I2C> Iqs231xDriver<I2C> {
     pub fn release_inner(self) -> I2C {
         self.i2c
     }
+
+    fn into_state<S2>(self) -> Iqs231xDriver<I2C, D, RDY, S2> {
+        Iqs231xDriver {
+            address: self.address,
+            i2c: self.i2c,
+            delay: self.delay,
+            retry: self.retry,
+            ready: self.ready,
+            ready_timeout_us: self.ready_timeout_us,
+            shadow: self.shadow,
+            state: PhantomData,
+        }
+    }
+}
+
+#[warn(missing_docs)]
+impl<I2C, D, RDY> Iqs231xDriver<I2C, D, RDY, Uninitialized> {
+    /// Moves straight to the [`Running`] state, for a device that is already sensing.
+    ///
+    /// Use this for devices running from their OTP settings, or when the device was
+    /// started before the MCU reset.
+    pub fn assume_running(self) -> Iqs231xDriver<I2C, D, RDY, Running> {
+        self.into_state()
+    }
+}
+
+#[warn(missing_docs)]
+impl<I2C, D, RDY> Iqs231xDriver<I2C, D, RDY, Running> {
+    /// Moves back to the [`Configured`] state to change settings.
+    ///
+    /// The new settings are only tuned for once [`start`](Iqs231xDriver::start) re-runs ATI.
+    pub fn reconfigure(self) -> Iqs231xDriver<I2C, D, RDY, Configured> {
+        self.into_state()
+    }
 }
 
 #[cfg(feature = "blocking")]
 impl<I2C, E> Iqs231xDriver<I2C>
-where I2C: embedded_hal::i2c::I2c<Error = E>
+where
+    I2C: embedded_hal::i2c::I2c<Error = E>,
+    E: embedded_hal::i2c::Error,
 {
-    pub fn product_number(&mut self) -> Result<u8, Iqs231xError<E>> {
-        let mut results: [u8; 2] = [0, 0];
+    /// Creates a new driver instance with the default I2C address, after checking that
+    /// the device answering at the address is an IQS231.
+    ///
+    /// Returns [`Iqs231xError::WrongDevice`] if another device answers.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # let i2c_interface = Mock::new(&[Transaction::write_read(0x44, vec![0x00], vec![0x00, 0x40, 0x06])]);
+    ///
+    /// let sensor = Iqs231xDriver::probe(i2c_interface).expect("no IQS231 found");
+    /// # sensor.release_inner().done();
+    /// ```
+    pub fn probe(i2c: I2C) -> Result<Self, Iqs231xError<E>> {
+        Self::probe_with_address(i2c, DEFAULT_ADDR)
+    }
 
-        self.i2c.write_read(self.address, &[PRODUCT_NUMBER_REG], &mut results)?;
+    /// Creates a new driver instance with a custom I2C address, after checking that
+    /// the device answering at the address is an IQS231.
+    ///
+    /// Returns [`Iqs231xError::WrongDevice`] if another device answers.
+    pub fn probe_with_address(i2c: I2C, addr: SevenBitAddress) -> Result<Self, Iqs231xError<E>> {
+        let mut driver = Self::with_address(i2c, addr);
+        driver.identify()?;
 
-        Ok(results[1])
+        Ok(driver)
     }
 }
 
-#[cfg(feature = "async")]
-impl<I2C, E> Iqs231xDriver<I2C>
-where I2C: embedded_hal_async::i2c::I2c<Error = E>
+#[cfg(feature = "blocking")]
+impl<I2C, D, RDY, S, E> Iqs231xDriver<I2C, D, RDY, S>
+where
+    I2C: embedded_hal::i2c::I2c<Error = E>,
+    D: embedded_hal::delay::DelayNs,
+    RDY: embedded_hal::digital::InputPin,
+    E: embedded_hal::i2c::Error,
 {
-    pub async fn product_number(&mut self) -> Result<u8, Iqs231xError<E>> {
+    pub fn product_number(&mut self) -> Result<u8, Iqs231xError<E>> {
         let mut results: [u8; 2] = [0, 0];
 
-        self.i2c.write_read(self.address, &[PRODUCT_NUMBER_REG], &mut results).await?;
+        self.read_registers(PRODUCT_NUMBER, &mut results)?;
 
         Ok(results[1])
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::iqs231x::DEFAULT_ADDR;
-    use crate::Iqs231xDriver;
-    use alloc::vec;
-    use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// Reads the device information and checks that the device is an IQS231.
+    ///
+    /// Returns [`Iqs231xError::WrongDevice`] if another device answers.
+    pub fn identify(&mut self) -> Result<DeviceInfo, Iqs231xError<E>> {
+        let info = self.device_info()?;
 
-    #[test]
-    fn test_product_number() {
-        let mut expectations = vec![];
+        if !info.is_iqs231() {
+            return Err(Iqs231xError::WrongDevice {
+                register: PRODUCT_NUMBER,
+                product_number: info.product_number,
+            });
+        }
 
-        expectations.extend_from_slice(&[
-            Transaction::write_read(DEFAULT_ADDR, vec![0x00], vec![0x00, 0x40])
-        ]);
+        Ok(info)
+    }
 
-        let mock = Mock::new(&expectations);
+    /// Reads the product and software number of the device in one transaction.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::iqs231x::Variant;
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # let i2c_interface = Mock::new(&[Transaction::write_read(0x44, vec![0x00], vec![0x00, 0x40, 0x0A])]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface);
+    /// let info = sensor.device_info().unwrap();
+    ///
+    /// assert_eq!(info.variant(), Some(Variant::Iqs231B));
+    /// # sensor.release_inner().done();
+    /// ```
+    pub fn device_info(&mut self) -> Result<DeviceInfo, Iqs231xError<E>> {
+        let mut results: [u8; 3] = [0; 3];
 
-        let mut sensor = Iqs231xDriver::new(mock);
-        let num = sensor.product_number().expect("Errored");
+        self.read_registers(PRODUCT_NUMBER, &mut results)?;
 
-        assert_eq!(num, 0x40);
+        Ok(DeviceInfo::from_bytes(results))
+    }
 
-        sensor.release_inner().done();
+    /// Moves the device to another I2C address in [`ADDR_RANGE`], through the address
+    /// selection in the shadow of OTP bank 0.
+    ///
+    /// The driver only switches to `addr` once the device identifies itself there. The
+    /// setting is lost at power-down unless it is programmed into OTP.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # let i2c_interface = Mock::new(&[
+    /// #     Transaction::write_read(0x44, vec![0x20], vec![0x50]),
+    /// #     Transaction::write(0x44, vec![0x20, 0x52]),
+    /// #     Transaction::write_read(0x46, vec![0x00], vec![0x00, 0x40, 0x06]),
+    /// # ]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface);
+    /// sensor.program_address(0x46).unwrap();
+    ///
+    /// assert_eq!(sensor.address(), 0x46);
+    /// # sensor.release_inner().done();
+    /// ```
+    pub fn program_address(&mut self, addr: SevenBitAddress) -> Result<(), Iqs231xError<E>> {
+        if !ADDR_RANGE.contains(&addr) {
+            return Err(Iqs231xError::InvalidValue(InvalidValue { register: OTP_SHADOW_0, value: u16::from(addr) }));
+        }
+
+        let mut shadow = [0; 1];
+        self.read_registers(OTP_SHADOW_0, &mut shadow)?;
+
+        let selection = (shadow[0] & !OtpShadow::ADDRESS_MASK) | (addr - DEFAULT_ADDR);
+        self.write_registers(OTP_SHADOW_0, &[selection])?;
+
+        self.follow_address(addr)
+    }
+
+    /// Switches the driver to `addr` once the device identifies itself there, keeping the
+    /// previous address on failure.
+    fn follow_address(&mut self, addr: SevenBitAddress) -> Result<(), Iqs231xError<E>> {
+        let previous = core::mem::replace(&mut self.address, addr);
+        if let Err(e) = self.identify() {
+            self.address = previous;
+            return Err(e);
+        }
+
+        Ok(())
     }
 
-}
\ No newline at end of file
+    /// Reads the OTP banks from their shadow registers, in one transaction.
+    ///
+    /// The shadows are loaded from OTP at reset, [`program_address`](Self::program_address)
+    /// changes the shadow of bank 0.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # let i2c_interface = Mock::new(&[Transaction::write_read(0x44, vec![0x20], vec![0x08, 24, 60, 0x35])]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface);
+    /// let otp = sensor.read_otp().unwrap().decode().unwrap();
+    ///
+    /// assert_eq!(otp.prox_threshold.get(), 24);
+    /// # sensor.release_inner().done();
+    /// ```
+    pub fn read_otp(&mut self) -> Result<OtpImage, Iqs231xError<E>> {
+        let mut results = [0; OTP_BANK_COUNT];
+
+        self.read_registers(OTP_SHADOW_0, &mut results)?;
+
+        Ok(OtpImage(results))
+    }
+
+    /// Waits for the communication window, signalled by the ready line going low.
+    fn wait_ready(&mut self, register: u8) -> Result<(), Iqs231xError<E>> {
+        let mut waited_us = 0;
+
+        loop {
+            match self.ready.is_low() {
+                Ok(true) => return Ok(()),
+                Ok(false) if waited_us < self.ready_timeout_us => {
+                    self.delay.delay_us(READY_POLL_INTERVAL_US);
+                    waited_us = waited_us.saturating_add(READY_POLL_INTERVAL_US);
+                }
+                Ok(false) => return Err(Iqs231xError::Timeout { register }),
+                Err(e) => return Err(Iqs231xError::ReadyPin { register, kind: embedded_hal::digital::Error::kind(&e) }),
+            }
+        }
+    }
+
+    /// Reads consecutive registers starting at `register` into `buffer`.
+    fn read_registers(&mut self, register: u8, buffer: &mut [u8]) -> Result<(), Iqs231xError<E>> {
+        let mut attempt = 1;
+
+        loop {
+            self.wait_ready(register)?;
+
+            let Err(e) = self.i2c.write_read(self.address, &[register], buffer) else {
+                break;
+            };
+
+            if !self.retry.should_retry(attempt, e.kind()) {
+                return Err(e.into());
+            }
+
+            trace!("iqs231x@{:#04x}: read {:#04x} NACKed, attempt {}", self.address, register, attempt);
+            self.delay.delay_us(self.retry.backoff_us);
+            attempt += 1;
+        }
+
+        trace!("iqs231x@{:#04x}: read {:#04x}: {:?}", self.address, register, buffer);
+
+        Ok(())
+    }
+
+    /// Writes `payload` to consecutive registers starting at `register`, in one transaction.
+    fn write_registers(&mut self, register: u8, payload: &[u8]) -> Result<(), Iqs231xError<E>> {
+        let mut buffer = [0u8; MAX_WRITE_LEN + 1];
+        buffer[0] = register;
+        buffer[1..=payload.len()].copy_from_slice(payload);
+
+        let mut attempt = 1;
+
+        loop {
+            self.wait_ready(register)?;
+
+            let Err(e) = self.i2c.write(self.address, &buffer[..=payload.len()]) else {
+                break;
+            };
+
+            if !self.retry.should_retry(attempt, e.kind()) {
+                return Err(e.into());
+            }
+
+            trace!("iqs231x@{:#04x}: write {:#04x} NACKed, attempt {}", self.address, register, attempt);
+            self.delay.delay_us(self.retry.backoff_us);
+            attempt += 1;
+        }
+
+        trace!("iqs231x@{:#04x}: write {:#04x}: {:?}", self.address, register, payload);
+
+        Ok(())
+    }
+
+    fn read_cached(&mut self, register: u8, buf: &mut [u8]) -> Result<(), Iqs231xError<E>> {
+        if let Some(shadow) = &self.shadow
+            && shadow.read(register, buf)
+        {
+            return Ok(());
+        }
+
+        self.read_registers(register, buf)
+    }
+
+    fn write_cached(&mut self, register: u8, payload: &[u8]) -> Result<(), Iqs231xError<E>> {
+        if let Some(shadow) = &mut self.shadow
+            && shadow.write(register, payload)
+        {
+            return Ok(());
+        }
+
+        self.write_registers(register, payload)
+    }
+
+    fn read_register<R: Register>(&mut self) -> Result<R, Iqs231xError<E>> {
+        let mut result: [u8; 1] = [0];
+
+        self.read_cached(R::ADDRESS, &mut result)?;
+
+        R::from_raw(result[0]).map_err(Iqs231xError::InvalidValue)
+    }
+
+    fn write_register<R: Register>(&mut self, value: R) -> Result<(), Iqs231xError<E>> {
+        self.write_cached(R::ADDRESS, &[value.into_raw()])?;
+
+        Ok(())
+    }
+
+    /// Writes `commands` to the command register, triggering every set command.
+    pub(crate) fn send_command(&mut self, commands: Commands) -> Result<(), Iqs231xError<E>> {
+        self.write_register(commands)
+    }
+
+    /// Acknowledges a device reset, clearing [`SystemFlags::show_reset`](crate::registers::SystemFlags::show_reset).
+    pub fn acknowledge_reset(&mut self) -> Result<(), Iqs231xError<E>> {
+        self.send_command(Commands { ack_reset: true, ..Default::default() })
+    }
+
+    /// Resets the device, returning to the [`Uninitialized`] state.
+    ///
+    /// The device restarts with the settings loaded from OTP, the shadow is disabled.
+    /// On failure the driver is returned unchanged along with the error.
+    pub fn soft_reset(mut self) -> Transition<Self, Iqs231xDriver<I2C, D, RDY, Uninitialized>, E> {
+        match self.send_command(Commands { soft_reset: true, ..Default::default() }) {
+            Ok(()) => {
+                self.shadow = None;
+                Ok(self.into_state())
+            }
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    /// Reads the proximity threshold.
+    pub fn prox_threshold(&mut self) -> Result<ProxThreshold, Iqs231xError<E>> {
+        self.read_register()
+    }
+
+    /// Reads the touch threshold.
+    pub fn touch_threshold(&mut self) -> Result<TouchThreshold, Iqs231xError<E>> {
+        self.read_register()
+    }
+
+    /// Reads the movement threshold.
+    pub fn movement_threshold(&mut self) -> Result<MovementThreshold, Iqs231xError<E>> {
+        self.read_register()
+    }
+
+    /// Reads the ATI settings and target in one transaction.
+    pub fn ati_config(&mut self) -> Result<AtiConfig, Iqs231xError<E>> {
+        let mut results: [u8; 2] = [0; 2];
+
+        self.read_cached(ATI_SETTINGS, &mut results)?;
+
+        Ok(AtiConfig::from_bytes(results))
+    }
+
+    /// Reads the power mode and report rate.
+    pub fn power_settings(&mut self) -> Result<PowerSettings, Iqs231xError<E>> {
+        self.read_register()
+    }
+
+    /// Reads the power mode.
+    pub fn power_mode(&mut self) -> Result<PowerMode, Iqs231xError<E>> {
+        Ok(self.power_settings()?.power_mode)
+    }
+
+    /// Reads the report rate.
+    pub fn report_rate(&mut self) -> Result<ReportRate, Iqs231xError<E>> {
+        Ok(self.power_settings()?.report_rate)
+    }
+
+    /// Reads the debug events of the device.
+    pub fn debug_events(&mut self) -> Result<DebugEvents, Iqs231xError<E>> {
+        self.read_register()
+    }
+
+    /// Reads the quick-release settings.
+    pub fn quick_release(&mut self) -> Result<QuickRelease, Iqs231xError<E>> {
+        self.read_register()
+    }
+
+    /// Reads the halt time of the LTA.
+    pub fn halt_time(&mut self) -> Result<HaltTime, Iqs231xError<E>> {
+        self.read_register()
+    }
+
+    /// Reads the counts and LTA filter settings.
+    pub fn filter_settings(&mut self) -> Result<FilterSettings, Iqs231xError<E>> {
+        self.read_register()
+    }
+
+    /// Reads the whole configuration block in one transaction.
+    ///
+    /// Fails with [`Iqs231xError::InvalidValue`] if a register holds a value outside the
+    /// range of its setting, such as the zero thresholds of a blank device.
+    pub fn read_config(&mut self) -> Result<Iqs231xConfig, Iqs231xError<E>> {
+        let mut results = [0; CONFIG_LEN];
+
+        self.read_registers(CONFIG_START, &mut results)?;
+
+        Iqs231xConfig::from_bytes(results).map_err(Iqs231xError::InvalidValue)
+    }
+
+    /// Reads the multipliers and compensation selected by the last ATI.
+    pub fn ati_result(&mut self) -> Result<AtiResult, Iqs231xError<E>> {
+        let mut results: [u8; 2] = [0; 2];
+
+        self.read_registers(ATI_MULTIPLIERS, &mut results)?;
+
+        Ok(AtiResult::from_bytes(results))
+    }
+
+    /// Polls the system flags every [`ATI_POLL_INTERVAL_US`] with `delay` until ATI completes,
+    /// returning the time waited in microseconds.
+    fn wait_for_ati<DL>(&mut self, delay: &mut DL) -> Result<u32, Iqs231xError<E>>
+    where
+        DL: embedded_hal::delay::DelayNs,
+    {
+        let mut waited_us = 0;
+
+        while !self.ati_done()? {
+            if waited_us >= ATI_TIMEOUT_US {
+                return Err(Iqs231xError::Timeout { register: SYSTEM_FLAGS });
+            }
+
+            delay.delay_us(ATI_POLL_INTERVAL_US);
+            waited_us = waited_us.saturating_add(ATI_POLL_INTERVAL_US);
+        }
+
+        Ok(waited_us)
+    }
+
+    /// Returns `true` once ATI completed, failing with [`Iqs231xError::AtiFailed`] if it could not reach its target.
+    fn ati_done(&mut self) -> Result<bool, Iqs231xError<E>> {
+        let flags: SystemFlags = self.read_register()?;
+
+        if flags.ati_error {
+            return Err(Iqs231xError::AtiFailed { register: SYSTEM_FLAGS });
+        }
+
+        Ok(!flags.ati_busy)
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<I2C, D, RDY, S, E> Iqs231xDriver<I2C, D, RDY, S>
+where
+    I2C: embedded_hal::i2c::I2c<Error = E>,
+    D: embedded_hal::delay::DelayNs,
+    RDY: embedded_hal::digital::InputPin,
+    S: Configurable,
+    E: embedded_hal::i2c::Error,
+{
+    /// Switches the device to the given communication mode.
+    pub fn set_communication_mode(&mut self, mode: CommunicationMode) -> Result<(), Iqs231xError<E>> {
+        self.send_command(mode.command())
+    }
+
+    /// Sets the proximity threshold.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::registers::ProxThreshold;
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # let i2c_interface = Mock::new(&[Transaction::write(0x44, vec![0x13, 0x10])]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface);
+    /// sensor.set_prox_threshold(ProxThreshold::new_const::<16>()).unwrap();
+    /// # sensor.release_inner().done();
+    /// ```
+    ///
+    /// The configuration cannot be changed while the device runs, go back with
+    /// [`reconfigure`](Iqs231xDriver::reconfigure) first:
+    ///
+    /// ```rust,compile_fail
+    /// use iqs231x_i2c::registers::ProxThreshold;
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # let i2c_interface = embedded_hal_mock::eh1::i2c::Mock::new(&[]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface).assume_running();
+    /// sensor.set_prox_threshold(ProxThreshold::new_const::<16>());
+    /// ```
+    pub fn set_prox_threshold(&mut self, threshold: ProxThreshold) -> Result<(), Iqs231xError<E>> {
+        self.write_register(threshold)
+    }
+
+    /// Sets the touch threshold.
+    pub fn set_touch_threshold(&mut self, threshold: TouchThreshold) -> Result<(), Iqs231xError<E>> {
+        self.write_register(threshold)
+    }
+
+    /// Sets the movement threshold.
+    pub fn set_movement_threshold(&mut self, threshold: MovementThreshold) -> Result<(), Iqs231xError<E>> {
+        self.write_register(threshold)
+    }
+
+    /// Writes the ATI settings and target in one transaction.
+    ///
+    /// The configuration takes effect on the next ATI, see [`redo_ati`](Self::redo_ati).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::iqs231x::AtiConfig;
+    /// use iqs231x_i2c::registers::{AtiBase, AtiMode, AtiSettings, AtiTarget};
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # let i2c_interface = Mock::new(&[Transaction::write(0x44, vec![0x11, 0x82, 0x40])]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface);
+    /// sensor.set_ati_config(AtiConfig {
+    ///     settings: AtiSettings { base: AtiBase::Base150, mode: AtiMode::Partial },
+    ///     target: AtiTarget::from_counts(512).unwrap(),
+    /// }).unwrap();
+    /// # sensor.release_inner().done();
+    /// ```
+    pub fn set_ati_config(&mut self, config: AtiConfig) -> Result<(), Iqs231xError<E>> {
+        let payload = config.to_bytes();
+
+        self.write_cached(ATI_SETTINGS, &payload)?;
+
+        Ok(())
+    }
+
+    /// Writes the power mode and report rate.
+    pub fn set_power_settings(&mut self, settings: PowerSettings) -> Result<(), Iqs231xError<E>> {
+        self.write_register(settings)
+    }
+
+    /// Sets the power mode, keeping the report rate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::registers::PowerMode;
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # let i2c_interface = Mock::new(&[
+    /// #     Transaction::write_read(0x44, vec![0x10], vec![0x08]),
+    /// #     Transaction::write(0x44, vec![0x10, 0x0A]),
+    /// # ]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface);
+    /// sensor.set_power_mode(PowerMode::UltraLowPower).unwrap();
+    /// # sensor.release_inner().done();
+    /// ```
+    pub fn set_power_mode(&mut self, mode: PowerMode) -> Result<(), Iqs231xError<E>> {
+        let mut settings = self.power_settings()?;
+        settings.power_mode = mode;
+
+        self.set_power_settings(settings)
+    }
+
+    /// Sets the report rate, keeping the power mode.
+    pub fn set_report_rate(&mut self, rate: ReportRate) -> Result<(), Iqs231xError<E>> {
+        let mut settings = self.power_settings()?;
+        settings.report_rate = rate;
+
+        self.set_power_settings(settings)
+    }
+
+    /// Writes the quick-release settings.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::registers::{QuickRelease, QuickReleaseThreshold};
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # let i2c_interface = Mock::new(&[Transaction::write(0x44, vec![0x16, 0x84])]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface);
+    /// sensor.set_quick_release(QuickRelease {
+    ///     threshold: QuickReleaseThreshold::new_const::<4>(),
+    ///     enabled: true,
+    /// }).unwrap();
+    /// # sensor.release_inner().done();
+    /// ```
+    pub fn set_quick_release(&mut self, settings: QuickRelease) -> Result<(), Iqs231xError<E>> {
+        self.write_register(settings)
+    }
+
+    /// Sets the halt time of the LTA.
+    pub fn set_halt_time(&mut self, halt_time: HaltTime) -> Result<(), Iqs231xError<E>> {
+        self.write_register(halt_time)
+    }
+
+    /// Writes the counts and LTA filter settings.
+    pub fn set_filter_settings(&mut self, settings: FilterSettings) -> Result<(), Iqs231xError<E>> {
+        self.write_register(settings)
+    }
+
+    /// Writes the whole configuration block in one transaction.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::{Iqs231xConfig, Iqs231xDriver};
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # let i2c_interface = Mock::new(&[
+    /// #     Transaction::write(0x44, vec![0x10, 0x08, 0x01, 0x40, 0x04, 0x20, 0x04, 0x04, 0x03, 0x05]),
+    /// # ]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface);
+    /// sensor.write_config(&Iqs231xConfig::default()).unwrap();
+    /// # sensor.release_inner().done();
+    /// ```
+    pub fn write_config(&mut self, config: &Iqs231xConfig) -> Result<(), Iqs231xError<E>> {
+        let payload = config.to_bytes();
+
+        self.write_registers(CONFIG_START, &payload)?;
+
+        if self.shadow.is_some() {
+            self.shadow = Some(ConfigShadow::new(payload));
+        }
+
+        Ok(())
+    }
+
+    /// Writes the whole configuration block, then reads it back and compares it.
+    ///
+    /// Reserved bits are ignored, see [`CONFIG_VERIFY_MASK`](crate::config::CONFIG_VERIFY_MASK).
+    /// Fails with [`Iqs231xError::VerifyMismatch`] naming the first register that differs.
+    pub fn write_config_verified(&mut self, config: &Iqs231xConfig) -> Result<(), Iqs231xError<E>> {
+        let mut results = [0; CONFIG_LEN];
+
+        self.write_config(config)?;
+        self.read_registers(CONFIG_START, &mut results)?;
+
+        config.verify(&results)
+    }
+
+    /// Reads the configuration block and starts caching it in the driver.
+    ///
+    /// Fails like [`read_config`](Self::read_config) if the block holds an invalid value.
+    ///
+    /// While the shadow is enabled the configuration getters and setters only access the
+    /// cache, the changes are written to the device by [`flush`](Self::flush).
+    /// [`read_config`](Self::read_config) and [`write_config`](Self::write_config) still access the device.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::registers::HaltTime;
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # let i2c_interface = Mock::new(&[
+    /// #     Transaction::write_read(0x44, vec![0x10], vec![0x08, 0x01, 0x40, 0x04, 0x20, 0x04, 0x04, 0x03, 0x05]),
+    /// #     Transaction::write(0x44, vec![0x17, 0x07]),
+    /// # ]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface);
+    /// sensor.enable_shadow().unwrap();
+    ///
+    /// // Only updates the shadow.
+    /// sensor.set_halt_time(HaltTime::Infinite).unwrap();
+    /// assert!(sensor.is_dirty());
+    ///
+    /// sensor.flush().unwrap();
+    /// # sensor.release_inner().done();
+    /// ```
+    pub fn enable_shadow(&mut self) -> Result<(), Iqs231xError<E>> {
+        let mut results = [0; CONFIG_LEN];
+
+        self.read_registers(CONFIG_START, &mut results)?;
+        Iqs231xConfig::from_bytes(results).map_err(Iqs231xError::InvalidValue)?;
+        self.shadow = Some(ConfigShadow::new(results));
+
+        Ok(())
+    }
+
+    /// Writes the configuration registers changed in the shadow, one transaction per run of
+    /// consecutive registers.
+    ///
+    /// Does nothing if the shadow is disabled.
+    pub fn flush(&mut self) -> Result<(), Iqs231xError<E>> {
+        let Some(mut shadow) = self.shadow else {
+            return Ok(());
+        };
+
+        while let Some(range) = shadow.next_dirty() {
+            self.write_registers(CONFIG_START + range.start as u8, &shadow.bytes()[range.clone()])?;
+            shadow.mark_synced(range);
+            self.shadow = Some(shadow);
+        }
+
+        Ok(())
+    }
+
+    /// Burns the image named by `token` into the OTP banks and verifies it.
+    ///
+    /// Writes the image to the shadow registers and follows the device to the address
+    /// selected by the image once it identifies itself there. If it does not, the previous
+    /// shadow contents are written back and nothing is programmed.
+    ///
+    /// Then programs the banks, waits [`OTP_PROGRAM_TIME_US`] with `delay` and soft-resets the
+    /// device, so it reloads the shadows from OTP. After [`POWER_UP_TIME_US`] the reloaded
+    /// shadows are read back, failing with [`Iqs231xError::VerifyMismatch`] naming the first
+    /// bank that differs. The device restarts with the programmed settings and the
+    /// configuration shadow is disabled, like after [`soft_reset`](Self::soft_reset).
+    #[cfg(feature = "otp-write")]
+    pub fn program_otp<DL>(&mut self, delay: &mut DL, token: OtpWriteToken) -> Result<(), Iqs231xError<E>>
+    where
+        DL: embedded_hal::delay::DelayNs,
+    {
+        let image = token.image();
+        let previous = self.read_otp()?;
+
+        self.write_registers(OTP_SHADOW_0, &image.0)?;
+        if let Err(e) = self.follow_address(image.address()) {
+            let _ = self.write_registers(OTP_SHADOW_0, &previous.0);
+            return Err(e);
+        }
+
+        self.write_registers(COMMANDS, &[otp::PROGRAM_COMMAND])?;
+        delay.delay_us(OTP_PROGRAM_TIME_US);
+
+        self.send_command(Commands { soft_reset: true, ..Default::default() })?;
+        self.shadow = None;
+        delay.delay_us(POWER_UP_TIME_US);
+        self.follow_address(image.address())?;
+
+        let found = self.read_otp()?;
+        otp::verify(&image, &found)
+    }
+
+    /// Writes `config` and moves to the [`Configured`] state.
+    ///
+    /// On failure the driver is returned unchanged along with the error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::{Iqs231xConfig, Iqs231xDriver};
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # let i2c_interface = Mock::new(&[
+    /// #     Transaction::write(0x44, vec![0x10, 0x08, 0x01, 0x40, 0x04, 0x20, 0x04, 0x04, 0x03, 0x05]),
+    /// #     Transaction::write(0x44, vec![0x04, 0x01]),
+    /// #     Transaction::write_read(0x44, vec![0x05], vec![0x00]),
+    /// #     Transaction::write_read(0x44, vec![0x05], vec![0x00, 0x01]),
+    /// # ]);
+    /// # let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    ///
+    /// let sensor = Iqs231xDriver::new(i2c_interface);
+    /// let sensor = sensor.configure(&Iqs231xConfig::default()).map_err(|(_, e)| e).unwrap();
+    /// let mut sensor = sensor.start(&mut delay).map_err(|(_, e)| e).unwrap();
+    ///
+    /// assert!(sensor.read_events().unwrap().proximity);
+    /// # sensor.release_inner().done();
+    /// ```
+    pub fn configure(mut self, config: &Iqs231xConfig) -> Transition<Self, Iqs231xDriver<I2C, D, RDY, Configured>, E> {
+        match self.write_config(config) {
+            Ok(()) => Ok(self.into_state()),
+            Err(e) => Err((self, e)),
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<I2C, D, RDY, E> Iqs231xDriver<I2C, D, RDY, Configured>
+where
+    I2C: embedded_hal::i2c::I2c<Error = E>,
+    D: embedded_hal::delay::DelayNs,
+    RDY: embedded_hal::digital::InputPin,
+    E: embedded_hal::i2c::Error,
+{
+    /// Runs ATI with the written configuration and moves to the [`Running`] state once it completes.
+    ///
+    /// Changes pending in the shadow are flushed first. The ATI progress is polled every
+    /// [`ATI_POLL_INTERVAL_US`] with `delay`, failing with [`Iqs231xError::AtiFailed`], or
+    /// [`Iqs231xError::Timeout`] after [`ATI_TIMEOUT_US`].
+    /// On failure the driver is returned unchanged along with the error.
+    pub fn start<DL>(mut self, delay: &mut DL) -> Transition<Self, Iqs231xDriver<I2C, D, RDY, Running>, E>
+    where
+        DL: embedded_hal::delay::DelayNs,
+    {
+        match self.run_ati(delay) {
+            Ok(()) => Ok(self.into_state()),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    fn run_ati<DL>(&mut self, delay: &mut DL) -> Result<(), Iqs231xError<E>>
+    where
+        DL: embedded_hal::delay::DelayNs,
+    {
+        self.flush()?;
+        self.send_command(Commands { redo_ati: true, ..Default::default() })?;
+
+        self.wait_for_ati(delay)?;
+
+        Ok(())
+    }
+}
+
+
+#[cfg(feature = "blocking")]
+impl<I2C, D, RDY, E> Iqs231xDriver<I2C, D, RDY, Uninitialized>
+where
+    I2C: embedded_hal::i2c::I2c<Error = E>,
+    D: embedded_hal::delay::DelayNs,
+    RDY: embedded_hal::digital::InputPin,
+    E: embedded_hal::i2c::Error,
+{
+    /// Brings up a freshly powered device and moves to the [`Running`] state.
+    ///
+    /// Waits [`POWER_UP_TIME_US`], acknowledges a pending reset, writes and verifies
+    /// `config`, then runs ATI and polls it with `delay` every [`ATI_POLL_INTERVAL_US`]. Fails with
+    /// [`Iqs231xError::AtiFailed`], or [`Iqs231xError::Timeout`] after [`ATI_TIMEOUT_US`].
+    /// On failure the driver is returned unchanged along with the error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::{Iqs231xConfig, Iqs231xDriver};
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # let config = [0x08, 0x01, 0x40, 0x04, 0x20, 0x04, 0x04, 0x03, 0x05];
+    /// # let mut write = vec![0x10];
+    /// # write.extend_from_slice(&config);
+    /// # let i2c_interface = Mock::new(&[
+    /// #     Transaction::write_read(0x44, vec![0x05], vec![0x80]),
+    /// #     Transaction::write(0x44, vec![0x04, 0x04]),
+    /// #     Transaction::write(0x44, write),
+    /// #     Transaction::write_read(0x44, vec![0x10], config.to_vec()),
+    /// #     Transaction::write(0x44, vec![0x04, 0x01]),
+    /// #     Transaction::write_read(0x44, vec![0x05], vec![0x00]),
+    /// #     Transaction::write_read(0x44, vec![0x0B], vec![0x13, 0x80]),
+    /// # ]);
+    /// # let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    ///
+    /// let sensor = Iqs231xDriver::new(i2c_interface);
+    /// let (sensor, report) = sensor.init(&mut delay, &Iqs231xConfig::default()).map_err(|(_, e)| e).unwrap();
+    ///
+    /// assert!(report.reset_acknowledged);
+    /// assert!(!report.ati.is_at_limit());
+    /// # sensor.release_inner().done();
+    /// ```
+    pub fn init<DL>(
+        mut self,
+        delay: &mut DL,
+        config: &Iqs231xConfig,
+    ) -> Transition<Self, Initialized<I2C, D, RDY>, E>
+    where
+        DL: embedded_hal::delay::DelayNs,
+    {
+        match self.run_init(delay, config) {
+            Ok(report) => Ok((self.into_state(), report)),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    fn run_init<DL>(&mut self, delay: &mut DL, config: &Iqs231xConfig) -> Result<InitReport, Iqs231xError<E>>
+    where
+        DL: embedded_hal::delay::DelayNs,
+    {
+        delay.delay_us(POWER_UP_TIME_US);
+
+        let flags: SystemFlags = self.read_register()?;
+        if flags.show_reset {
+            self.acknowledge_reset()?;
+        }
+
+        self.write_config_verified(config)?;
+        self.send_command(Commands { redo_ati: true, ..Default::default() })?;
+
+        let ati_time_us = self.wait_for_ati(delay)?;
+
+        Ok(InitReport {
+            reset_acknowledged: flags.show_reset,
+            ati: self.ati_result()?,
+            ati_time_us,
+        })
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<I2C, D, RDY, E> Iqs231xDriver<I2C, D, RDY, Running>
+where
+    I2C: embedded_hal::i2c::I2c<Error = E>,
+    D: embedded_hal::delay::DelayNs,
+    RDY: embedded_hal::digital::InputPin,
+    E: embedded_hal::i2c::Error,
+{
+    /// Reads the system flags and main events of the device in one transaction.
+    ///
+    /// Fails with [`Iqs231xError::DeviceReset`] if the device reset, as it no longer runs the
+    /// configuration of the driver. The error repeats until the reset is acknowledged, see
+    /// [`acknowledge_reset`](Self::acknowledge_reset).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # let i2c_interface = Mock::new(&[Transaction::write_read(0x44, vec![0x05], vec![0x00, 0x01])]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface).assume_running();
+    /// let events = sensor.read_events().unwrap();
+    ///
+    /// assert!(events.proximity);
+    /// assert!(!events.touch);
+    /// # sensor.release_inner().done();
+    /// ```
+    ///
+    /// Events are only read from a running device, the call is rejected before
+    /// [`init`](Iqs231xDriver::init) or [`start`](Iqs231xDriver::start):
+    ///
+    /// ```rust,compile_fail
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # let i2c_interface = embedded_hal_mock::eh1::i2c::Mock::new(&[]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface);
+    /// let events = sensor.read_events();
+    /// ```
+    ///
+    /// ```rust,compile_fail
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # let i2c_interface = embedded_hal_mock::eh1::i2c::Mock::new(&[]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface).assume_running().reconfigure();
+    /// let events = sensor.read_events();
+    /// ```
+    pub fn read_events(&mut self) -> Result<Events, Iqs231xError<E>> {
+        let mut results: [u8; 2] = [0; 2];
+
+        self.read_registers(SYSTEM_FLAGS, &mut results)?;
+
+        let events = Events::from_bytes(results);
+        if events.device_reset {
+            return Err(Iqs231xError::DeviceReset { register: SYSTEM_FLAGS });
+        }
+
+        Ok(events)
+    }
+
+    /// Reads the raw channel counts.
+    pub fn counts(&mut self) -> Result<Counts, Iqs231xError<E>> {
+        let mut results: [u8; 2] = [0; 2];
+
+        self.read_registers(COUNTS, &mut results)?;
+
+        Ok(Counts::from_be_bytes(results))
+    }
+
+    /// Reads the long-term average of the channel counts.
+    pub fn lta(&mut self) -> Result<Lta, Iqs231xError<E>> {
+        let mut results: [u8; 2] = [0; 2];
+
+        self.read_registers(LTA, &mut results)?;
+
+        Ok(Lta::from_be_bytes(results))
+    }
+
+    /// Reads the counts and the LTA in one transaction and derives the delta from them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # let i2c_interface = Mock::new(&[Transaction::write_read(0x44, vec![0x07], vec![0x01, 0xF4, 0x02, 0x00])]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface).assume_running();
+    /// let channel = sensor.read_channel().unwrap();
+    ///
+    /// assert_eq!(channel.counts.0, 500);
+    /// assert_eq!(channel.lta.0, 512);
+    /// assert_eq!(channel.delta.0, 12);
+    /// # sensor.release_inner().done();
+    /// ```
+    ///
+    /// Like [`read_events`](Self::read_events), the call is rejected before the device runs:
+    ///
+    /// ```rust,compile_fail
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # let i2c_interface = embedded_hal_mock::eh1::i2c::Mock::new(&[]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface);
+    /// let channel = sensor.read_channel();
+    /// ```
+    ///
+    /// ```rust,compile_fail
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # let i2c_interface = embedded_hal_mock::eh1::i2c::Mock::new(&[]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface).assume_running().reconfigure();
+    /// let channel = sensor.read_channel();
+    /// ```
+    pub fn read_channel(&mut self) -> Result<ChannelData, Iqs231xError<E>> {
+        let mut results: [u8; 4] = [0; 4];
+
+        self.read_registers(COUNTS, &mut results)?;
+
+        Ok(ChannelData::from_bytes(results))
+    }
+
+    /// Re-runs the automatic tuning implementation (ATI).
+    ///
+    /// The progress of the ATI is reported by [`Events::ati_busy`] and [`Events::ati_error`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::Iqs231xDriver;
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # let i2c_interface = Mock::new(&[Transaction::write(0x44, vec![0x04, 0x01])]);
+    ///
+    /// let mut sensor = Iqs231xDriver::new(i2c_interface).assume_running();
+    /// sensor.redo_ati().unwrap();
+    /// # sensor.release_inner().done();
+    /// ```
+    pub fn redo_ati(&mut self) -> Result<(), Iqs231xError<E>> {
+        self.send_command(Commands { redo_ati: true, ..Default::default() })
+    }
+
+    /// Reseeds the long-term average with the current counts.
+    pub fn reseed(&mut self) -> Result<(), Iqs231xError<E>> {
+        self.send_command(Commands { reseed: true, ..Default::default() })
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    extern crate std;
+
+    use crate::iqs231x::{AtiConfig, AtiResult, CommunicationMode, Delta, Event, Events, InitReport, Variant, DEFAULT_ADDR};
+    use crate::registers::{AtiBase, AtiMode, AtiSettings, AtiTarget, Counts, FilterBeta, FilterSettings, HaltTime, InvalidValue, Lta, MovementThreshold, PowerMode, ProductNumber, ProxThreshold, ReportRate, TouchThreshold};
+    use crate::retry::RetryPolicy;
+    use crate::{Iqs231xConfig, Iqs231xDriver, Iqs231xError};
+    use alloc::vec;
+    use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+    use embedded_hal_mock::eh1::delay;
+    use embedded_hal_mock::eh1::digital;
+    use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    use embedded_hal_mock::eh1::MockError;
+    use std::io;
+
+    #[test]
+    fn test_product_number() {
+        let mut expectations = vec![];
+
+        expectations.extend_from_slice(&[
+            Transaction::write_read(DEFAULT_ADDR, vec![0x00], vec![0x00, 0x40])
+        ]);
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        let num = sensor.product_number().expect("Errored");
+
+        assert_eq!(num, 0x40);
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_device_info() {
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x00], vec![0x00, 0x40, 0x06])
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        let info = sensor.device_info().expect("Errored");
+
+        assert_eq!(info.product_number, ProductNumber::IQS231);
+        assert_eq!(info.variant(), Some(Variant::Iqs231A));
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_probe_wrong_device() {
+        let expectations = [
+            Transaction::write_read(0x45, vec![0x00], vec![0x00, 0x3C, 0x01])
+        ];
+
+        let mut mock = Mock::new(&expectations);
+
+        let result = Iqs231xDriver::probe_with_address(mock.clone(), 0x45);
+        assert_eq!(result.err(), Some(Iqs231xError::WrongDevice {
+            register: 0x00,
+            product_number: ProductNumber(0x003C),
+        }));
+
+        mock.done();
+    }
+
+    #[test]
+    fn test_read_events() {
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0b1000_0010, 0b0000_1110]),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0x04]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0b0000_0010, 0b0000_1110]),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock).assume_running();
+        assert_eq!(sensor.read_events(), Err(Iqs231xError::DeviceReset { register: 0x05 }));
+
+        sensor.acknowledge_reset().expect("Errored");
+        let events = sensor.read_events().expect("Errored");
+
+        assert_eq!(events, Events {
+            proximity: false,
+            touch: true,
+            movement: true,
+            quick_release: true,
+            ati_busy: false,
+            ati_error: true,
+            device_reset: false,
+        });
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_counts_and_lta() {
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x07], vec![0x03, 0x20]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x09], vec![0x03, 0x84]),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock).assume_running();
+
+        assert_eq!(sensor.counts().expect("Errored"), Counts(800));
+        assert_eq!(sensor.lta().expect("Errored"), Lta(900));
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_delta_saturates() {
+        assert_eq!(Delta::new(Counts(0), Lta(u16::MAX)), Delta(i16::MAX));
+        assert_eq!(Delta::new(Counts(u16::MAX), Lta(0)), Delta(i16::MIN));
+        assert_eq!(Delta::new(Counts(520), Lta(500)), Delta(-20));
+    }
+
+    #[test]
+    fn test_commands() {
+        let expectations = [
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0b0000_0100]),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0b0001_0000]),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0b0010_0000]),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0b0000_0010]),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0b1000_0000]),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        sensor.acknowledge_reset().expect("Errored");
+        sensor.set_communication_mode(CommunicationMode::Event).expect("Errored");
+        sensor.set_communication_mode(CommunicationMode::Streaming).expect("Errored");
+
+        let mut sensor = sensor.assume_running();
+        sensor.reseed().expect("Errored");
+        let sensor = sensor.soft_reset().map_err(|(_, e)| e).expect("Errored");
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_thresholds() {
+        let expectations = [
+            Transaction::write(DEFAULT_ADDR, vec![0x13, 24]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x13], vec![24]),
+            Transaction::write(DEFAULT_ADDR, vec![0x14, 60]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x14], vec![60]),
+            Transaction::write(DEFAULT_ADDR, vec![0x15, 5]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x15], vec![5]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x13], vec![0]),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+
+        let prox = ProxThreshold::new(24).expect("Invalid");
+        sensor.set_prox_threshold(prox).expect("Errored");
+        assert_eq!(sensor.prox_threshold().expect("Errored"), prox);
+
+        let touch = TouchThreshold::new(60).expect("Invalid");
+        sensor.set_touch_threshold(touch).expect("Errored");
+        assert_eq!(sensor.touch_threshold().expect("Errored"), touch);
+
+        let movement = MovementThreshold::new(5).expect("Invalid");
+        sensor.set_movement_threshold(movement).expect("Errored");
+        assert_eq!(sensor.movement_threshold().expect("Errored"), movement);
+
+        assert_eq!(
+            sensor.prox_threshold(),
+            Err(Iqs231xError::InvalidValue(InvalidValue { register: 0x13, value: 0 }))
+        );
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_ati_config_and_result() {
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x11], vec![0x00, 0x80]),
+            Transaction::write(DEFAULT_ADDR, vec![0x11, 0x83, 0x80]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x0B], vec![0b1001_0111, 0x2C]),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+
+        let mut config = sensor.ati_config().expect("Errored");
+        assert_eq!(config, AtiConfig {
+            settings: AtiSettings { base: AtiBase::Base75, mode: AtiMode::Full },
+            target: AtiTarget::from_counts(1024).expect("Invalid"),
+        });
+
+        config.settings = AtiSettings { base: AtiBase::Base200, mode: AtiMode::Partial };
+        sensor.set_ati_config(config).expect("Errored");
+
+        let mut sensor = sensor.assume_running();
+        let result = sensor.ati_result().expect("Errored");
+        assert_eq!(result, AtiResult {
+            sensitivity_multiplier: 7,
+            compensation_multiplier: 1,
+            compensation: 0x22C,
+        });
+        assert!(!result.is_at_limit());
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_power_mode_and_report_rate() {
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x10], vec![0b0000_1000]),
+            Transaction::write(DEFAULT_ADDR, vec![0x10, 0b0000_1001]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x10], vec![0b0000_1001]),
+            Transaction::write(DEFAULT_ADDR, vec![0x10, 0b0001_0101]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x10], vec![0b0001_0101]),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        sensor.set_power_mode(PowerMode::LowPower).expect("Errored");
+        sensor.set_report_rate(ReportRate::Ms256).expect("Errored");
+        assert_eq!(sensor.report_rate().expect("Errored"), ReportRate::Ms256);
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_release_settings() {
+        let expectations = [
+            Transaction::write(DEFAULT_ADDR, vec![0x17, 0x07]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x17], vec![0x07]),
+            Transaction::write(DEFAULT_ADDR, vec![0x18, 0b0001_1000]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x03], vec![0b0000_1000]),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        sensor.set_halt_time(HaltTime::Infinite).expect("Errored");
+        assert_eq!(sensor.halt_time().expect("Errored"), HaltTime::Infinite);
+
+        sensor.set_filter_settings(FilterSettings {
+            counts_beta: FilterBeta::Beta1,
+            lta_beta: FilterBeta::Beta3,
+            counts_filter_disabled: true,
+        }).expect("Errored");
+
+        assert!(sensor.debug_events().expect("Errored").halt_timeout);
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_config_burst() {
+        let bytes = [0x12, 0x01, 0x40, 0x04, 0x20, 0x04, 0x84, 0x07, 0x05];
+        let mut write = vec![0x10];
+        write.extend_from_slice(&bytes);
+
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x10], bytes.to_vec()),
+            Transaction::write(DEFAULT_ADDR, write),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        let config = sensor.read_config().expect("Errored");
+        assert_eq!(config.power.power_mode, PowerMode::UltraLowPower);
+        assert_eq!(config.halt_time, HaltTime::Infinite);
+        assert!(config.quick_release.enabled);
+
+        sensor.write_config(&config).expect("Errored");
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_config_verified_mismatch() {
+        let config = Iqs231xConfig::default();
+        let mut write = vec![0x10];
+        write.extend_from_slice(&config.to_bytes());
+        let mut read = config.to_bytes().to_vec();
+        read[0] |= 0x80;
+        read[7] = 0x00;
+
+        let expectations = [
+            Transaction::write(DEFAULT_ADDR, write),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x10], read),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        let error = sensor.write_config_verified(&config).unwrap_err();
+        assert_eq!(error, Iqs231xError::VerifyMismatch { register: 0x17, expected: 0x03, found: 0x00 });
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_shadow_flush() {
+        let config = Iqs231xConfig::default();
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x10], config.to_bytes().to_vec()),
+            Transaction::write(DEFAULT_ADDR, vec![0x10, 0x0C]),
+            Transaction::write(DEFAULT_ADDR, vec![0x13, 0x05, 0x21]),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        sensor.enable_shadow().expect("Errored");
+        assert!(!sensor.is_dirty());
+
+        sensor.set_report_rate(ReportRate::Ms64).expect("Errored");
+        assert_eq!(sensor.power_mode().expect("Errored"), PowerMode::Normal);
+        sensor.set_prox_threshold(ProxThreshold::new_const::<5>()).expect("Errored");
+        sensor.set_touch_threshold(TouchThreshold::new_const::<33>()).expect("Errored");
+        sensor.set_halt_time(HaltTime::Seconds20).expect("Errored");
+        assert!(sensor.is_dirty());
+        assert_eq!(sensor.shadow_config().expect("Disabled").prox_threshold.get(), 5);
+
+        sensor.flush().expect("Errored");
+        assert!(!sensor.is_dirty());
+        sensor.flush().expect("Errored");
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_lifecycle() {
+        let config = Iqs231xConfig::default();
+        let mut write = vec![0x10];
+        write.extend_from_slice(&config.to_bytes());
+
+        let expectations = [
+            Transaction::write(DEFAULT_ADDR, write),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0x01]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x01]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x00]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x00, 0x01]),
+            Transaction::write(DEFAULT_ADDR, vec![0x13, 0x08]),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0x01]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x02]),
+        ];
+        let delays = [
+            delay::Transaction::delay_us(10_000),
+        ];
+
+        let mock = Mock::new(&expectations);
+        let mut delay = delay::CheckedDelay::new(&delays);
+
+        let sensor = Iqs231xDriver::new(mock)
+            .configure(&config)
+            .map_err(|(_, e)| e)
+            .expect("Errored");
+        let mut sensor = sensor.start(&mut delay).map_err(|(_, e)| e).expect("Errored");
+        assert!(sensor.read_events().expect("Errored").proximity);
+
+        let mut sensor = sensor.reconfigure();
+        sensor.set_prox_threshold(ProxThreshold::new_const::<8>()).expect("Errored");
+        let (sensor, error) = sensor.start(&mut delay).expect_err("Started");
+        assert_eq!(error, Iqs231xError::AtiFailed { register: 0x05 });
+
+        sensor.release_inner().done();
+        delay.done();
+    }
+
+    #[test]
+    fn test_init() {
+        let config = Iqs231xConfig::default();
+        let mut write = vec![0x10];
+        write.extend_from_slice(&config.to_bytes());
+
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x80]),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0x04]),
+            Transaction::write(DEFAULT_ADDR, write),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x10], config.to_bytes().to_vec()),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0x01]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x01]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x01]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x00]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x0B], vec![0b0001_0011, 0x80]),
+        ];
+        let delays = [
+            delay::Transaction::delay_us(20_000),
+            delay::Transaction::delay_us(10_000),
+            delay::Transaction::delay_us(10_000),
+        ];
+
+        let mock = Mock::new(&expectations);
+        let mut delay = delay::CheckedDelay::new(&delays);
+
+        let (sensor, report) = Iqs231xDriver::new(mock)
+            .init(&mut delay, &config)
+            .map_err(|(_, e)| e)
+            .expect("Errored");
+
+        assert_eq!(report, InitReport {
+            reset_acknowledged: true,
+            ati: AtiResult { sensitivity_multiplier: 3, compensation_multiplier: 1, compensation: 0x80 },
+            ati_time_us: 20_000,
+        });
+
+        sensor.release_inner().done();
+        delay.done();
+    }
+
+    #[test]
+    fn test_program_address() {
+        let nack = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x20], vec![0xA0]),
+            Transaction::write(DEFAULT_ADDR, vec![0x20, 0xA3]),
+            Transaction::write_read(0x47, vec![0x00], vec![0x00, 0x40, 0x0A]),
+            Transaction::write_read(0x47, vec![0x20], vec![0xA3]),
+            Transaction::write(0x47, vec![0x20, 0xA1]),
+            Transaction::write_read(0x45, vec![0x00], vec![0x00, 0x00, 0x00]).with_error(nack),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        assert_eq!(
+            sensor.program_address(0x48),
+            Err(Iqs231xError::InvalidValue(InvalidValue { register: 0x20, value: 0x48 }))
+        );
+
+        sensor.program_address(0x47).expect("Errored");
+        assert_eq!(sensor.address(), 0x47);
+
+        assert_eq!(sensor.program_address(0x45), Err(Iqs231xError::I2CError(nack)));
+        assert_eq!(sensor.address(), 0x47);
+
+        sensor.release_inner().done();
+    }
+
+    #[test]
+    fn test_read_otp() {
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x20], vec![0x00, 0x00, 0x00, 0x00]),
+        ];
+
+        let mock = Mock::new(&expectations);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        assert!(sensor.read_otp().expect("Errored").is_blank());
+
+        sensor.release_inner().done();
+    }
+
+    #[cfg(feature = "otp-write")]
+    #[test]
+    fn test_program_otp() {
+        use crate::otp::{OtpImage, OtpWriteToken};
+
+        let nack = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+        let image = OtpImage([0x89, 24, 60, 0x35]);
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x20], vec![0x88, 24, 60, 0x35]),
+            Transaction::write(DEFAULT_ADDR, vec![0x20, 0x8A, 24, 60, 0x35]),
+            Transaction::write_read(0x46, vec![0x00], vec![0x00, 0x00, 0x00]).with_error(nack),
+            Transaction::write(DEFAULT_ADDR, vec![0x20, 0x88, 24, 60, 0x35]),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x20], vec![0x88, 24, 60, 0x35]),
+            Transaction::write(DEFAULT_ADDR, vec![0x20, 0x89, 24, 60, 0x35]),
+            Transaction::write_read(0x45, vec![0x00], vec![0x00, 0x40, 0x06]),
+            Transaction::write(0x45, vec![0x04, 0x40]),
+            Transaction::write(0x45, vec![0x04, 0x80]),
+            Transaction::write_read(0x45, vec![0x00], vec![0x00, 0x40, 0x06]),
+            Transaction::write_read(0x45, vec![0x20], vec![0x89, 24, 60, 0x35]),
+            Transaction::write_read(0x45, vec![0x20], vec![0x89, 24, 60, 0x35]),
+            Transaction::write(0x45, vec![0x20, 0x89, 24, 60, 0x35]),
+            Transaction::write_read(0x45, vec![0x00], vec![0x00, 0x40, 0x06]),
+            Transaction::write(0x45, vec![0x04, 0x40]),
+            Transaction::write(0x45, vec![0x04, 0x80]),
+            Transaction::write_read(0x45, vec![0x00], vec![0x00, 0x40, 0x06]),
+            Transaction::write_read(0x45, vec![0x20], vec![0x89, 24, 0x00, 0x35]),
+        ];
+        let delays = [
+            delay::Transaction::delay_us(50_000),
+            delay::Transaction::delay_us(20_000),
+            delay::Transaction::delay_us(50_000),
+            delay::Transaction::delay_us(20_000),
+        ];
+
+        let mock = Mock::new(&expectations);
+        let mut delay = delay::CheckedDelay::new(&delays);
+
+        let mut sensor = Iqs231xDriver::new(mock);
+        assert_eq!(
+            sensor.program_otp(&mut delay, OtpWriteToken::confirm_permanent_write(OtpImage([0x8A, 24, 60, 0x35]))),
+            Err(Iqs231xError::I2CError(nack))
+        );
+        assert_eq!(sensor.address(), DEFAULT_ADDR);
+
+        sensor.program_otp(&mut delay, OtpWriteToken::confirm_permanent_write(image)).expect("Errored");
+        assert_eq!(sensor.address(), 0x45);
+
+        assert_eq!(
+            sensor.program_otp(&mut delay, OtpWriteToken::confirm_permanent_write(image)),
+            Err(Iqs231xError::VerifyMismatch { register: 0x22, expected: 60, found: 0 })
+        );
+
+        sensor.release_inner().done();
+        delay.done();
+    }
+
+    #[test]
+    fn test_retry_on_nack() {
+        let nack = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+        let expectations = [
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x00, 0x00]).with_error(nack),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x00, 0x00]).with_error(nack),
+            Transaction::write_read(DEFAULT_ADDR, vec![0x05], vec![0x00, 0x01]),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0x02]).with_error(nack),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0x02]).with_error(nack),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0x02]).with_error(nack),
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0x01]).with_error(ErrorKind::Bus),
+        ];
+        let delays = [
+            delay::Transaction::delay_us(250),
+            delay::Transaction::delay_us(250),
+            delay::Transaction::delay_us(250),
+            delay::Transaction::delay_us(250),
+        ];
+
+        let mock = Mock::new(&expectations);
+        let mut delay = delay::CheckedDelay::new(&delays);
+
+        let mut sensor = Iqs231xDriver::new(mock)
+            .with_retry(&mut delay, RetryPolicy::new(3, 250))
+            .assume_running();
+
+        assert!(sensor.read_events().expect("Errored").proximity);
+        assert_eq!(sensor.reseed(), Err(Iqs231xError::I2CError(nack)));
+        assert_eq!(sensor.redo_ati(), Err(Iqs231xError::I2CError(ErrorKind::Bus)));
+
+        sensor.release_inner().done();
+        delay.done();
+    }
+
+    #[test]
+    fn test_ready_pin() {
+        let expectations = [
+            Transaction::write(DEFAULT_ADDR, vec![0x04, 0x02]),
+        ];
+        let pin_expectations = [
+            digital::Transaction::get(digital::State::High),
+            digital::Transaction::get(digital::State::High),
+            digital::Transaction::get(digital::State::Low),
+            digital::Transaction::get(digital::State::High),
+            digital::Transaction::get(digital::State::High),
+            digital::Transaction::get(digital::State::Low).with_error(MockError::Io(io::ErrorKind::Other)),
+        ];
+        let delays = [
+            delay::Transaction::delay_us(50),
+            delay::Transaction::delay_us(50),
+            delay::Transaction::delay_us(50),
+        ];
+
+        let mock = Mock::new(&expectations);
+        let mut pin = digital::Mock::new(&pin_expectations);
+        let mut delay = delay::CheckedDelay::new(&delays);
+
+        let mut sensor = Iqs231xDriver::new(mock)
+            .with_ready_pin(pin.clone(), &mut delay)
+            .assume_running();
+
+        sensor.reseed().expect("Errored");
+
+        sensor.set_ready_timeout_us(50);
+        assert_eq!(sensor.redo_ati(), Err(Iqs231xError::Timeout { register: 0x04 }));
+        assert_eq!(sensor.reseed(), Err(Iqs231xError::ReadyPin { register: 0x04, kind: embedded_hal::digital::ErrorKind::Other }));
+
+        sensor.release_inner().done();
+        pin.done();
+        delay.done();
+    }
+
+    #[test]
+    fn test_event_changes() {
+        let idle = Events::default();
+        let touched = Events { proximity: true, touch: true, movement: true, ..Default::default() };
+        let released = Events { quick_release: true, ..Default::default() };
+
+        assert!(touched.changes_since(&idle).eq([Event::ProxEnter, Event::TouchDown, Event::Movement]));
+        assert!(released.changes_since(&touched).eq([Event::TouchUp, Event::QuickRelease, Event::ProxExit]));
+        assert_eq!(touched.changes_since(&touched).next(), None);
+    }
+}
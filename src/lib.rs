@@ -4,8 +4,25 @@
 #[cfg(test)]
 extern crate alloc; // Only required when running tests
 
+#[cfg(feature = "defmt-03")]
+extern crate defmt_03 as defmt;
+
+#[macro_use]
+mod fmt;
+
+#[cfg(feature = "async")]
+pub mod asynch;
+pub mod config;
 pub mod error;
 pub mod iqs231x;
+pub mod otp;
+pub mod ready;
+pub mod registers;
+pub mod retry;
+pub mod scan;
+pub mod single_wire;
+pub mod state;
 
+pub use config::Iqs231xConfig;
 pub use error::Iqs231xError;
 pub use iqs231x::Iqs231xDriver;
\ No newline at end of file
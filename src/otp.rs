@@ -0,0 +1,254 @@
+//! One-time programmable (OTP) configuration.
+//!
+//! At reset the IQS231 loads its OTP banks into the shadow registers [`OTP_SHADOW_0`] to
+//! [`OTP_SHADOW_3`]. A device without a host runs from these settings alone, every other
+//! setting starts at its reset value. The banks are laid out as:
+//!
+//! | Bank | Bits | Setting                                        |
+//! |------|------|------------------------------------------------|
+//! | 0    | 1:0  | I2C address, added to [`DEFAULT_ADDR`]         |
+//! | 0    | 3:2  | [`PowerMode`](crate::registers::PowerMode)     |
+//! | 0    | 6:4  | [`ReportRate`](crate::registers::ReportRate)   |
+//! | 0    | 7    | quick release enable                           |
+//! | 1    | 7:0  | [`ProxThreshold`]                              |
+//! | 2    | 7:0  | [`TouchThreshold`]                             |
+//! | 3    | 3:0  | [`MovementThreshold`]                          |
+//! | 3    | 6:4  | [`HaltTime`]                                   |
+//! | 3    | 7    | [`AtiMode::Partial`]                           |
+//!
+//! [`OtpImage::encode`] generates the bank contents from an [`OtpConfig`], which
+//! [`OtpConfig::from_config`] takes from an [`Iqs231xConfig`]. Both work without a device,
+//! so images for standalone devices can be generated and reviewed on the host.
+//!
+//! Programming is only available with the `otp-write` feature, see `Iqs231xDriver::program_otp`.
+
+use embedded_hal::i2c::SevenBitAddress;
+
+use crate::config::Iqs231xConfig;
+use crate::iqs231x::{ADDR_RANGE, DEFAULT_ADDR};
+use crate::registers::{
+    AtiMode, HaltTime, InvalidValue, MovementThreshold, OtpShadow, PowerSettings, ProxThreshold, TouchThreshold,
+    OTP_BANK_COUNT, OTP_SHADOW_0, OTP_SHADOW_1, OTP_SHADOW_2, OTP_SHADOW_3,
+};
+
+/// Time the device needs to program its OTP banks, in microseconds.
+#[cfg(feature = "otp-write")]
+pub const OTP_PROGRAM_TIME_US: u32 = 50_000;
+
+/// Contents of [`COMMANDS`](crate::registers::COMMANDS) that program the OTP banks with
+/// their shadow registers.
+#[cfg(all(feature = "otp-write", any(feature = "blocking", feature = "async")))]
+pub(crate) const PROGRAM_COMMAND: u8 = 1 << 6;
+
+/// Contents of the OTP banks, as read from the shadow registers.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct OtpImage(pub [u8; OTP_BANK_COUNT]);
+
+impl OtpImage {
+    /// Contents of unprogrammed OTP.
+    pub const BLANK: OtpImage = OtpImage([0; OTP_BANK_COUNT]);
+
+    /// Encodes `config` into the bank contents.
+    ///
+    /// Fails if the address is outside of [`ADDR_RANGE`].
+    pub fn encode(config: &OtpConfig) -> Result<Self, InvalidValue> {
+        if !ADDR_RANGE.contains(&config.address) {
+            return Err(InvalidValue { register: OTP_SHADOW_0, value: u16::from(config.address) });
+        }
+
+        let partial = matches!(config.ati_mode, AtiMode::Partial);
+
+        Ok(Self([
+            (config.address - DEFAULT_ADDR) | u8::from(config.power) << 2 | u8::from(config.quick_release_enabled) << 7,
+            config.prox_threshold.get(),
+            config.touch_threshold.get(),
+            config.movement_threshold.get() | u8::from(config.halt_time) << 4 | u8::from(partial) << 7,
+        ]))
+    }
+
+    /// Creates the image from its bit pattern, bank 0 in the most significant byte.
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits.to_be_bytes())
+    }
+
+    /// Returns the bit pattern of the image, bank 0 in the most significant byte.
+    pub const fn bits(&self) -> u32 {
+        u32::from_be_bytes(self.0)
+    }
+
+    /// Returns `true` if no bank is programmed.
+    pub fn is_blank(&self) -> bool {
+        *self == Self::BLANK
+    }
+
+    /// Returns the I2C address selected by bank 0.
+    pub fn address(&self) -> SevenBitAddress {
+        DEFAULT_ADDR + (self.0[0] & OtpShadow::ADDRESS_MASK)
+    }
+
+    /// Decodes the settings held by the banks.
+    ///
+    /// Fails with the bank holding a threshold of zero, which unprogrammed banks do.
+    pub fn decode(&self) -> Result<OtpConfig, InvalidValue> {
+        let [bank0, bank1, bank2, bank3] = self.0;
+        let invalid = |register, value: u8| InvalidValue { register, value: u16::from(value) };
+
+        Ok(OtpConfig {
+            address: self.address(),
+            power: PowerSettings::from(bank0 >> 2),
+            quick_release_enabled: bank0 & 0x80 != 0,
+            prox_threshold: ProxThreshold::new(bank1).map_err(|_| invalid(OTP_SHADOW_1, bank1))?,
+            touch_threshold: TouchThreshold::new(bank2).map_err(|_| invalid(OTP_SHADOW_2, bank2))?,
+            movement_threshold: MovementThreshold::new(bank3 & 0x0F).map_err(|_| invalid(OTP_SHADOW_3, bank3))?,
+            halt_time: HaltTime::from(bank3 >> 4),
+            ati_mode: if bank3 & 0x80 != 0 { AtiMode::Partial } else { AtiMode::Full },
+        })
+    }
+}
+
+/// Settings held by the OTP banks, see the [module documentation](self) for the layout.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct OtpConfig {
+    pub address: SevenBitAddress,
+    pub power: PowerSettings,
+    pub quick_release_enabled: bool,
+    pub prox_threshold: ProxThreshold,
+    pub touch_threshold: TouchThreshold,
+    pub movement_threshold: MovementThreshold,
+    pub halt_time: HaltTime,
+    pub ati_mode: AtiMode,
+}
+
+impl OtpConfig {
+    /// Takes the settings the OTP can hold from `config`, for a device at `address`.
+    ///
+    /// The quick-release threshold, the ATI base and target and the filter settings
+    /// cannot be stored in OTP and are left out.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::otp::{OtpConfig, OtpImage};
+    /// use iqs231x_i2c::Iqs231xConfig;
+    ///
+    /// let otp = OtpConfig::from_config(0x45, &Iqs231xConfig::default());
+    /// let image = OtpImage::encode(&otp).unwrap();
+    ///
+    /// assert_eq!(image.0, [0x21, 0x04, 0x20, 0x34]);
+    /// assert_eq!(image.bits(), 0x2104_2034);
+    /// assert_eq!(image.decode(), Ok(otp));
+    /// ```
+    pub fn from_config(address: SevenBitAddress, config: &Iqs231xConfig) -> Self {
+        Self {
+            address,
+            power: config.power,
+            quick_release_enabled: config.quick_release.enabled,
+            prox_threshold: config.prox_threshold,
+            touch_threshold: config.touch_threshold,
+            movement_threshold: config.movement_threshold,
+            halt_time: config.halt_time,
+            ati_mode: config.ati.settings.mode,
+        }
+    }
+
+    /// Overwrites the settings of `config` held by the OTP, keeping the others.
+    pub fn apply(&self, config: &mut Iqs231xConfig) {
+        config.power = self.power;
+        config.quick_release.enabled = self.quick_release_enabled;
+        config.prox_threshold = self.prox_threshold;
+        config.touch_threshold = self.touch_threshold;
+        config.movement_threshold = self.movement_threshold;
+        config.halt_time = self.halt_time;
+        config.ati.settings.mode = self.ati_mode;
+    }
+}
+
+/// Confirmation required by the drivers' `program_otp`, naming the image to burn into the
+/// OTP banks.
+///
+/// Programming cannot be undone, a token is used up by a single programming attempt.
+#[cfg(feature = "otp-write")]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct OtpWriteToken {
+    image: OtpImage,
+}
+
+#[cfg(feature = "otp-write")]
+impl OtpWriteToken {
+    /// Confirms that `image` is to be programmed permanently.
+    pub fn confirm_permanent_write(image: OtpImage) -> Self {
+        Self { image }
+    }
+
+    /// Returns the image to program.
+    pub fn image(&self) -> OtpImage {
+        self.image
+    }
+}
+
+/// Checks that `found`, read back after programming, matches `expected`.
+#[cfg(all(feature = "otp-write", any(feature = "blocking", feature = "async")))]
+pub(crate) fn verify<E>(expected: &OtpImage, found: &OtpImage) -> Result<(), crate::Iqs231xError<E>> {
+    for (bank, (expected, found)) in expected.0.iter().zip(found.0).enumerate() {
+        if *expected != found {
+            return Err(crate::Iqs231xError::VerifyMismatch {
+                register: OtpShadow::address(bank),
+                expected: *expected,
+                found,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::otp::{OtpConfig, OtpImage};
+    use crate::registers::{AtiMode, HaltTime, InvalidValue, PowerMode, ReportRate};
+    use crate::Iqs231xConfig;
+
+    #[test]
+    fn test_decode() {
+        let config = OtpImage([0b1101_0110, 24, 60, 0b1101_0101]).decode().expect("Invalid");
+
+        assert_eq!(config.address, 0x46);
+        assert_eq!(config.power.power_mode, PowerMode::LowPower);
+        assert_eq!(config.power.report_rate, ReportRate::Ms256);
+        assert!(config.quick_release_enabled);
+        assert_eq!(config.prox_threshold.get(), 24);
+        assert_eq!(config.touch_threshold.get(), 60);
+        assert_eq!(config.movement_threshold.get(), 5);
+        assert_eq!(config.halt_time, HaltTime::Seconds80);
+        assert_eq!(config.ati_mode, AtiMode::Partial);
+
+        assert!(OtpImage::BLANK.is_blank());
+        assert_eq!(OtpImage::BLANK.decode(), Err(InvalidValue { register: 0x21, value: 0 }));
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let mut config = Iqs231xConfig::default();
+        config.power.power_mode = PowerMode::UltraLowPower;
+        config.power.report_rate = ReportRate::Ms1024;
+        config.quick_release.enabled = true;
+        config.halt_time = HaltTime::Infinite;
+        config.ati.settings.mode = AtiMode::Partial;
+
+        let otp = OtpConfig::from_config(0x47, &config);
+        let image = OtpImage::encode(&otp).expect("Invalid");
+        assert_eq!(image.0, [0b1111_1011, 0x04, 0x20, 0xF4]);
+        assert_eq!(OtpImage::from_bits(image.bits()), image);
+        assert_eq!(image.decode(), Ok(otp));
+
+        let mut decoded = Iqs231xConfig::default();
+        image.decode().expect("Invalid").apply(&mut decoded);
+        assert_eq!(decoded, config);
+
+        let otp = OtpConfig { address: 0x48, ..otp };
+        assert_eq!(OtpImage::encode(&otp), Err(InvalidValue { register: 0x20, value: 0x48 }));
+    }
+}
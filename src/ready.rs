@@ -0,0 +1,60 @@
+//! Ready (RDY/IO) line handshake.
+//!
+//! The IQS231 pulls its IO line low while its communication window is open. When the
+//! driver owns this line, see [`with_ready_pin`](crate::Iqs231xDriver::with_ready_pin),
+//! every transaction waits for the window instead of being NACKed by the device.
+
+use core::convert::Infallible;
+#[cfg(feature = "async")]
+use embedded_hal::digital::ErrorKind;
+
+/// Default time to wait for the communication window, in microseconds.
+pub const DEFAULT_READY_TIMEOUT_US: u32 = 100_000;
+
+/// Interval at which the blocking driver polls the ready line, in microseconds.
+pub const READY_POLL_INTERVAL_US: u32 = 50;
+
+/// Placeholder for an unconnected ready line, reporting the communication window as always open.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct NoPin;
+
+impl embedded_hal::digital::ErrorType for NoPin {
+    type Error = Infallible;
+}
+
+impl embedded_hal::digital::InputPin for NoPin {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+}
+
+/// Ready line the async driver waits on before every transaction.
+///
+/// Implemented for every [`Wait`](embedded_hal_async::digital::Wait) pin, and for [`NoPin`],
+/// which reports the communication window as always open. [`NoPin`] does not implement
+/// `Wait`, so APIs that sleep until the device signals, such as
+/// [`event_stream`](crate::asynch::Iqs231xDriver::event_stream), require a connected line.
+#[cfg(feature = "async")]
+pub trait WaitReady {
+    /// Waits for the communication window, signalled by the line going low.
+    fn wait_ready(&mut self) -> impl Future<Output = Result<(), ErrorKind>>;
+}
+
+#[cfg(feature = "async")]
+impl<P: embedded_hal_async::digital::Wait> WaitReady for P {
+    async fn wait_ready(&mut self) -> Result<(), ErrorKind> {
+        self.wait_for_low().await.map_err(|e| embedded_hal::digital::Error::kind(&e))
+    }
+}
+
+#[cfg(feature = "async")]
+impl WaitReady for NoPin {
+    async fn wait_ready(&mut self) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+}
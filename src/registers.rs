@@ -0,0 +1,964 @@
+//! Register map of the IQS231A/B.
+//!
+//! Every address of the I2C memory map is named here, together with a typed
+//! representation of its contents. Single byte registers implement [`Register`],
+//! which ties the type to its address and provides the conversion from and to the
+//! raw byte on the bus. Multi byte values (counts, LTA) are transmitted MSB first.
+
+/// Product number, 16 bit, MSB first (read only).
+pub const PRODUCT_NUMBER: u8 = 0x00;
+/// Software (firmware) version number (read only).
+pub const SOFTWARE_NUMBER: u8 = 0x02;
+/// Debug events (read only).
+pub const DEBUG_EVENTS: u8 = 0x03;
+/// Command register (write only, bits clear themselves once executed).
+///
+/// Bit 6 programs the OTP banks, it is only written by the drivers' `program_otp` (with the
+/// `otp-write` feature) and left out of [`Commands`].
+pub const COMMANDS: u8 = 0x04;
+/// System flags (read only).
+pub const SYSTEM_FLAGS: u8 = 0x05;
+/// Main events (read only).
+pub const MAIN_EVENTS: u8 = 0x06;
+/// Channel counts, 16 bit, MSB first (read only).
+pub const COUNTS: u8 = 0x07;
+/// Long-term average, 16 bit, MSB first (read only).
+pub const LTA: u8 = 0x09;
+/// ATI multipliers selected by the last ATI (read only).
+pub const ATI_MULTIPLIERS: u8 = 0x0B;
+/// Low byte of the ATI compensation selected by the last ATI (read only).
+pub const ATI_COMPENSATION: u8 = 0x0C;
+
+/// Power mode and report rate settings.
+pub const POWER_SETTINGS: u8 = 0x10;
+/// ATI mode and base settings.
+pub const ATI_SETTINGS: u8 = 0x11;
+/// ATI target counts.
+pub const ATI_TARGET: u8 = 0x12;
+/// Proximity threshold.
+pub const PROX_THRESHOLD: u8 = 0x13;
+/// Touch threshold.
+pub const TOUCH_THRESHOLD: u8 = 0x14;
+/// Movement threshold.
+pub const MOVEMENT_THRESHOLD: u8 = 0x15;
+/// Quick-release settings.
+pub const QUICK_RELEASE: u8 = 0x16;
+/// Halt time of the LTA filter.
+pub const HALT_TIME: u8 = 0x17;
+/// Counts and LTA filter settings.
+pub const FILTER_SETTINGS: u8 = 0x18;
+
+/// First register of the configuration block.
+pub const CONFIG_START: u8 = POWER_SETTINGS;
+/// Number of registers in the configuration block.
+pub const CONFIG_LEN: usize = (FILTER_SETTINGS - CONFIG_START + 1) as usize;
+
+/// Shadow of OTP bank 0.
+pub const OTP_SHADOW_0: u8 = 0x20;
+/// Shadow of OTP bank 1.
+pub const OTP_SHADOW_1: u8 = 0x21;
+/// Shadow of OTP bank 2.
+pub const OTP_SHADOW_2: u8 = 0x22;
+/// Shadow of OTP bank 3.
+pub const OTP_SHADOW_3: u8 = 0x23;
+
+/// Number of OTP banks (and their shadow registers).
+pub const OTP_BANK_COUNT: usize = 4;
+
+/// A single byte register of the IQS231A/B.
+pub trait Register: Copy {
+    /// Address of the register in the memory map.
+    const ADDRESS: u8;
+
+    /// Decodes the raw contents of the register.
+    ///
+    /// Fails if the contents are outside the range of the type, as the thresholds of a
+    /// blank device are.
+    fn from_raw(raw: u8) -> Result<Self, InvalidValue>;
+
+    /// Encodes the value into the raw contents of the register.
+    fn into_raw(self) -> u8;
+}
+
+macro_rules! register {
+    ($ty:ty, $addr:expr) => {
+        impl Register for $ty {
+            const ADDRESS: u8 = $addr;
+
+            fn from_raw(raw: u8) -> Result<Self, InvalidValue> {
+                Ok(Self::from(raw))
+            }
+
+            fn into_raw(self) -> u8 {
+                self.into()
+            }
+        }
+    };
+    (@try $ty:ty, $addr:expr) => {
+        impl Register for $ty {
+            const ADDRESS: u8 = $addr;
+
+            fn from_raw(raw: u8) -> Result<Self, InvalidValue> {
+                Self::try_from(raw)
+            }
+
+            fn into_raw(self) -> u8 {
+                self.into()
+            }
+        }
+    };
+}
+
+/// Error returned when a value is outside the valid range of a register field.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct InvalidValue {
+    /// Address of the register the value was meant for.
+    pub register: u8,
+    /// The rejected value.
+    pub value: u16,
+}
+
+impl core::fmt::Display for InvalidValue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "value {} is out of range for register {:#04x}", self.value, self.register)
+    }
+}
+
+impl core::error::Error for InvalidValue {}
+
+const fn in_range(value: u8, min: u8, max: u8) -> bool {
+    value >= min && value <= max
+}
+
+/// Defines a validated threshold newtype, accepting values in `$min..=$max`.
+/// `$max` doubles as the mask of the field within the register.
+///
+/// The `@field` form is used for thresholds sharing their register with other settings,
+/// and does not implement [`Register`].
+macro_rules! threshold {
+    ($(#[$meta:meta])* $ty:ident, $addr:expr, $min:expr, $max:expr) => {
+        threshold!(@field $(#[$meta])* $ty, $addr, $min, $max);
+
+        impl Register for $ty {
+            const ADDRESS: u8 = $addr;
+
+            /// Decodes the register, ignoring the bits outside the field.
+            fn from_raw(raw: u8) -> Result<Self, InvalidValue> {
+                Self::new(raw & $max)
+            }
+
+            fn into_raw(self) -> u8 {
+                self.0
+            }
+        }
+    };
+    (@field $(#[$meta:meta])* $ty:ident, $addr:expr, $min:expr, $max:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+        #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+        pub struct $ty(u8);
+
+        impl $ty {
+            /// Smallest valid threshold.
+            pub const MIN: $ty = $ty($min);
+            /// Largest valid threshold.
+            pub const MAX: $ty = $ty($max);
+
+            /// Creates a threshold, returning an error if `value` is out of range.
+            pub const fn new(value: u8) -> Result<Self, InvalidValue> {
+                if !in_range(value, $min, $max) {
+                    Err(InvalidValue { register: $addr, value: value as u16 })
+                } else {
+                    Ok(Self(value))
+                }
+            }
+
+            /// Creates a threshold from a constant, failing to compile if `VALUE` is out of range.
+            pub const fn new_const<const VALUE: u8>() -> Self {
+                const { assert!(in_range(VALUE, $min, $max), "threshold out of range") };
+                Self(VALUE)
+            }
+
+            /// Returns the threshold, in counts.
+            pub const fn get(self) -> u8 {
+                self.0
+            }
+        }
+
+        impl TryFrom<u8> for $ty {
+            type Error = InvalidValue;
+
+            fn try_from(value: u8) -> Result<Self, Self::Error> {
+                Self::new(value)
+            }
+        }
+
+        impl From<$ty> for u8 {
+            fn from(value: $ty) -> Self {
+                value.0
+            }
+        }
+    };
+}
+
+const fn bit(value: u8, n: u8) -> bool {
+    value & (1 << n) != 0
+}
+
+const fn set_bit(value: u8, n: u8, set: bool) -> u8 {
+    if set { value | (1 << n) } else { value & !(1 << n) }
+}
+
+/// Product number of the device, read from [`PRODUCT_NUMBER`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ProductNumber(pub u16);
+
+impl ProductNumber {
+    /// Product number reported by both the IQS231A and the IQS231B.
+    pub const IQS231: ProductNumber = ProductNumber(0x0040);
+
+    /// Decodes the product number from the two bytes read at [`PRODUCT_NUMBER`].
+    pub fn from_be_bytes(bytes: [u8; 2]) -> Self {
+        Self(u16::from_be_bytes(bytes))
+    }
+}
+
+/// Software (firmware) version of the device.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct SoftwareNumber(pub u8);
+
+impl From<u8> for SoftwareNumber {
+    fn from(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+impl From<SoftwareNumber> for u8 {
+    fn from(value: SoftwareNumber) -> Self {
+        value.0
+    }
+}
+
+register!(SoftwareNumber, SOFTWARE_NUMBER);
+
+impl SoftwareNumber {
+    /// Software number reported by the IQS231A.
+    pub const IQS231A: SoftwareNumber = SoftwareNumber(0x06);
+    /// Software number reported by the IQS231B.
+    pub const IQS231B: SoftwareNumber = SoftwareNumber(0x0A);
+}
+
+/// Internal events of the device, mainly useful while debugging a design.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct DebugEvents {
+    /// An ATI was performed since the last read.
+    pub ati_event: bool,
+    /// The LTA was reseeded since the last read.
+    pub reseed_event: bool,
+    /// The LTA is currently halted.
+    pub lta_halted: bool,
+    /// A proximity was released by the halt timer.
+    pub halt_timeout: bool,
+}
+
+impl From<u8> for DebugEvents {
+    fn from(value: u8) -> Self {
+        Self {
+            ati_event: bit(value, 0),
+            reseed_event: bit(value, 1),
+            lta_halted: bit(value, 2),
+            halt_timeout: bit(value, 3),
+        }
+    }
+}
+
+impl From<DebugEvents> for u8 {
+    fn from(value: DebugEvents) -> Self {
+        let mut raw = 0;
+        raw = set_bit(raw, 0, value.ati_event);
+        raw = set_bit(raw, 1, value.reseed_event);
+        raw = set_bit(raw, 2, value.lta_halted);
+        set_bit(raw, 3, value.halt_timeout)
+    }
+}
+
+register!(DebugEvents, DEBUG_EVENTS);
+
+/// Command register. Every set bit triggers the corresponding action once.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Commands {
+    /// Re-run the automatic tuning implementation (ATI).
+    pub redo_ati: bool,
+    /// Reseed the long-term average with the current counts.
+    pub reseed: bool,
+    /// Acknowledge a device reset, clearing [`SystemFlags::show_reset`].
+    pub ack_reset: bool,
+    /// Switch to event mode (the device only communicates on events).
+    pub event_mode: bool,
+    /// Switch to streaming mode (the device communicates every cycle).
+    pub streaming_mode: bool,
+    /// Reset the device.
+    pub soft_reset: bool,
+}
+
+impl From<u8> for Commands {
+    fn from(value: u8) -> Self {
+        Self {
+            redo_ati: bit(value, 0),
+            reseed: bit(value, 1),
+            ack_reset: bit(value, 2),
+            event_mode: bit(value, 4),
+            streaming_mode: bit(value, 5),
+            soft_reset: bit(value, 7),
+        }
+    }
+}
+
+impl From<Commands> for u8 {
+    fn from(value: Commands) -> Self {
+        let mut raw = 0;
+        raw = set_bit(raw, 0, value.redo_ati);
+        raw = set_bit(raw, 1, value.reseed);
+        raw = set_bit(raw, 2, value.ack_reset);
+        raw = set_bit(raw, 4, value.event_mode);
+        raw = set_bit(raw, 5, value.streaming_mode);
+        set_bit(raw, 7, value.soft_reset)
+    }
+}
+
+register!(Commands, COMMANDS);
+
+/// System flags, reporting the state of the device.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct SystemFlags {
+    /// ATI is currently running.
+    pub ati_busy: bool,
+    /// The last ATI could not reach the target counts.
+    pub ati_error: bool,
+    /// The device is in event mode.
+    pub event_mode: bool,
+    /// The device has reset since the last reset acknowledgement.
+    pub show_reset: bool,
+}
+
+impl From<u8> for SystemFlags {
+    fn from(value: u8) -> Self {
+        Self {
+            ati_busy: bit(value, 0),
+            ati_error: bit(value, 1),
+            event_mode: bit(value, 4),
+            show_reset: bit(value, 7),
+        }
+    }
+}
+
+impl From<SystemFlags> for u8 {
+    fn from(value: SystemFlags) -> Self {
+        let mut raw = 0;
+        raw = set_bit(raw, 0, value.ati_busy);
+        raw = set_bit(raw, 1, value.ati_error);
+        raw = set_bit(raw, 4, value.event_mode);
+        set_bit(raw, 7, value.show_reset)
+    }
+}
+
+register!(SystemFlags, SYSTEM_FLAGS);
+
+/// Main events, the sensing outputs of the device.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct MainEvents {
+    /// Proximity is detected.
+    pub proximity: bool,
+    /// Touch is detected.
+    pub touch: bool,
+    /// Movement is detected.
+    pub movement: bool,
+    /// A proximity was released by the quick-release detection.
+    pub quick_release: bool,
+}
+
+impl From<u8> for MainEvents {
+    fn from(value: u8) -> Self {
+        Self {
+            proximity: bit(value, 0),
+            touch: bit(value, 1),
+            movement: bit(value, 2),
+            quick_release: bit(value, 3),
+        }
+    }
+}
+
+impl From<MainEvents> for u8 {
+    fn from(value: MainEvents) -> Self {
+        let mut raw = 0;
+        raw = set_bit(raw, 0, value.proximity);
+        raw = set_bit(raw, 1, value.touch);
+        raw = set_bit(raw, 2, value.movement);
+        set_bit(raw, 3, value.quick_release)
+    }
+}
+
+register!(MainEvents, MAIN_EVENTS);
+
+/// Raw channel counts, read from [`COUNTS`].
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Counts(pub u16);
+
+impl Counts {
+    /// Decodes the counts from the two bytes read at [`COUNTS`].
+    pub fn from_be_bytes(bytes: [u8; 2]) -> Self {
+        Self(u16::from_be_bytes(bytes))
+    }
+}
+
+/// Long-term average of the channel counts, read from [`LTA`].
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Lta(pub u16);
+
+impl Lta {
+    /// Decodes the LTA from the two bytes read at [`LTA`].
+    pub fn from_be_bytes(bytes: [u8; 2]) -> Self {
+        Self(u16::from_be_bytes(bytes))
+    }
+}
+
+/// ATI multipliers, as selected by the last ATI.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct AtiMultipliers {
+    /// Sensitivity (coarse) multiplier, 4 bits.
+    pub sensitivity: u8,
+    /// Compensation multiplier, 2 bits.
+    pub compensation: u8,
+    /// Bits 9:8 of the ATI compensation, the low byte is in [`ATI_COMPENSATION`].
+    pub compensation_high: u8,
+}
+
+impl From<u8> for AtiMultipliers {
+    fn from(value: u8) -> Self {
+        Self {
+            sensitivity: value & 0x0F,
+            compensation: (value >> 4) & 0x03,
+            compensation_high: value >> 6,
+        }
+    }
+}
+
+impl From<AtiMultipliers> for u8 {
+    fn from(value: AtiMultipliers) -> Self {
+        (value.sensitivity & 0x0F) | ((value.compensation & 0x03) << 4) | (value.compensation_high << 6)
+    }
+}
+
+register!(AtiMultipliers, ATI_MULTIPLIERS);
+
+/// Low byte of the ATI compensation, as selected by the last ATI.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct AtiCompensation(pub u8);
+
+impl From<u8> for AtiCompensation {
+    fn from(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+impl From<AtiCompensation> for u8 {
+    fn from(value: AtiCompensation) -> Self {
+        value.0
+    }
+}
+
+register!(AtiCompensation, ATI_COMPENSATION);
+
+/// Power mode of the device, trading response time for current consumption.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum PowerMode {
+    /// The channel is sampled every cycle.
+    #[default]
+    Normal,
+    /// The channel is sampled at the report rate, waking up to normal mode on proximity.
+    LowPower,
+    /// The channel is sampled at the report rate with a reduced charge cycle,
+    /// waking up to normal mode on proximity.
+    UltraLowPower,
+}
+
+impl PowerMode {
+    /// Decodes the power mode, the reserved value `0b11` decodes as [`PowerMode::UltraLowPower`].
+    fn from_bits(bits: u8) -> Self {
+        match bits & 0x03 {
+            0 => PowerMode::Normal,
+            1 => PowerMode::LowPower,
+            _ => PowerMode::UltraLowPower,
+        }
+    }
+
+    fn bits(self) -> u8 {
+        match self {
+            PowerMode::Normal => 0,
+            PowerMode::LowPower => 1,
+            PowerMode::UltraLowPower => 2,
+        }
+    }
+}
+
+/// Interval between samples while in [`PowerMode::LowPower`] or [`PowerMode::UltraLowPower`].
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum ReportRate {
+    Ms8,
+    Ms16,
+    #[default]
+    Ms32,
+    Ms64,
+    Ms128,
+    Ms256,
+    Ms512,
+    Ms1024,
+}
+
+impl ReportRate {
+    /// Returns the interval between samples, in milliseconds.
+    pub fn millis(self) -> u16 {
+        8 << self.bits()
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        match bits & 0x07 {
+            0 => ReportRate::Ms8,
+            1 => ReportRate::Ms16,
+            2 => ReportRate::Ms32,
+            3 => ReportRate::Ms64,
+            4 => ReportRate::Ms128,
+            5 => ReportRate::Ms256,
+            6 => ReportRate::Ms512,
+            _ => ReportRate::Ms1024,
+        }
+    }
+
+    fn bits(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Power mode and report rate settings.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct PowerSettings {
+    pub power_mode: PowerMode,
+    pub report_rate: ReportRate,
+}
+
+impl From<u8> for PowerSettings {
+    fn from(value: u8) -> Self {
+        Self {
+            power_mode: PowerMode::from_bits(value),
+            report_rate: ReportRate::from_bits(value >> 2),
+        }
+    }
+}
+
+impl From<PowerSettings> for u8 {
+    fn from(value: PowerSettings) -> Self {
+        value.power_mode.bits() | (value.report_rate.bits() << 2)
+    }
+}
+
+register!(PowerSettings, POWER_SETTINGS);
+
+/// Base value of the ATI, the counts the ATI starts from before compensating.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum AtiBase {
+    Base75,
+    #[default]
+    Base100,
+    Base150,
+    Base200,
+}
+
+impl AtiBase {
+    /// Returns the base value, in counts.
+    pub fn counts(self) -> u16 {
+        match self {
+            AtiBase::Base75 => 75,
+            AtiBase::Base100 => 100,
+            AtiBase::Base150 => 150,
+            AtiBase::Base200 => 200,
+        }
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        match bits & 0x03 {
+            0 => AtiBase::Base75,
+            1 => AtiBase::Base100,
+            2 => AtiBase::Base150,
+            _ => AtiBase::Base200,
+        }
+    }
+
+    fn bits(self) -> u8 {
+        match self {
+            AtiBase::Base75 => 0,
+            AtiBase::Base100 => 1,
+            AtiBase::Base150 => 2,
+            AtiBase::Base200 => 3,
+        }
+    }
+}
+
+/// Which parameters the ATI is allowed to adjust.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum AtiMode {
+    /// Adjust the multipliers and the compensation.
+    #[default]
+    Full,
+    /// Keep the multipliers and only adjust the compensation.
+    Partial,
+}
+
+/// ATI mode and base settings.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct AtiSettings {
+    pub base: AtiBase,
+    pub mode: AtiMode,
+}
+
+impl From<u8> for AtiSettings {
+    fn from(value: u8) -> Self {
+        Self {
+            base: AtiBase::from_bits(value),
+            mode: if bit(value, 7) { AtiMode::Partial } else { AtiMode::Full },
+        }
+    }
+}
+
+impl From<AtiSettings> for u8 {
+    fn from(value: AtiSettings) -> Self {
+        set_bit(value.base.bits(), 7, value.mode == AtiMode::Partial)
+    }
+}
+
+register!(AtiSettings, ATI_SETTINGS);
+
+/// ATI target, in units of 8 counts.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct AtiTarget(pub u8);
+
+impl AtiTarget {
+    /// Counts represented by one step of the target.
+    pub const STEP: u16 = 8;
+
+    /// Creates a target from a number of counts, returning an error if `counts` is not
+    /// a multiple of [`STEP`](Self::STEP) or does not fit the register.
+    pub const fn from_counts(counts: u16) -> Result<Self, InvalidValue> {
+        let steps = counts / Self::STEP;
+
+        if !counts.is_multiple_of(Self::STEP) || steps > u8::MAX as u16 {
+            return Err(InvalidValue { register: ATI_TARGET, value: counts });
+        }
+
+        Ok(Self(steps as u8))
+    }
+
+    /// Returns the target, in counts.
+    pub const fn counts(self) -> u16 {
+        self.0 as u16 * Self::STEP
+    }
+}
+
+impl From<u8> for AtiTarget {
+    fn from(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+impl From<AtiTarget> for u8 {
+    fn from(value: AtiTarget) -> Self {
+        value.0
+    }
+}
+
+register!(AtiTarget, ATI_TARGET);
+
+threshold!(
+    /// Proximity threshold, in counts (1 to 255).
+    ProxThreshold, PROX_THRESHOLD, 1, 0xFF
+);
+
+threshold!(
+    /// Touch threshold, in counts (1 to 255).
+    TouchThreshold, TOUCH_THRESHOLD, 1, 0xFF
+);
+
+threshold!(
+    /// Movement threshold, in counts (1 to 15).
+    MovementThreshold, MOVEMENT_THRESHOLD, 1, 0x0F
+);
+
+threshold!(@field
+    /// Quick-release threshold, in counts (1 to 15).
+    ///
+    /// A detected proximity is released as soon as the delta drops by more than this
+    /// threshold, instead of waiting for it to fall below the proximity threshold.
+    QuickReleaseThreshold, QUICK_RELEASE, 1, 0x0F
+);
+
+/// Quick-release settings.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct QuickRelease {
+    pub threshold: QuickReleaseThreshold,
+    /// Quick-release detection is enabled.
+    pub enabled: bool,
+}
+
+impl TryFrom<u8> for QuickRelease {
+    type Error = InvalidValue;
+
+    /// Decodes the settings, failing on a threshold of zero only if quick release is enabled.
+    ///
+    /// The threshold of a disabled quick release is unused, so zero decodes to
+    /// [`QuickReleaseThreshold::MIN`].
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        let enabled = bit(value, 7);
+        let threshold = match QuickReleaseThreshold::new(value & 0x0F) {
+            Err(_) if !enabled => QuickReleaseThreshold::MIN,
+            threshold => threshold?,
+        };
+
+        Ok(Self { threshold, enabled })
+    }
+}
+
+impl From<QuickRelease> for u8 {
+    fn from(value: QuickRelease) -> Self {
+        set_bit(value.threshold.get(), 7, value.enabled)
+    }
+}
+
+register!(@try QuickRelease, QUICK_RELEASE);
+
+/// How long the LTA is halted while a proximity is detected.
+///
+/// Once the halt time runs out the LTA follows the counts again, releasing the proximity.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum HaltTime {
+    /// The LTA is never halted.
+    Disabled,
+    Seconds5,
+    Seconds10,
+    #[default]
+    Seconds20,
+    Seconds40,
+    Seconds80,
+    Seconds160,
+    /// The LTA stays halted for as long as the proximity is detected.
+    Infinite,
+}
+
+impl HaltTime {
+    /// Returns the halt time in seconds, or `None` for [`Disabled`](HaltTime::Disabled)
+    /// and [`Infinite`](HaltTime::Infinite).
+    pub fn seconds(self) -> Option<u16> {
+        match self {
+            HaltTime::Disabled | HaltTime::Infinite => None,
+            _ => Some(5 << (self as u8 - 1)),
+        }
+    }
+}
+
+impl From<u8> for HaltTime {
+    fn from(value: u8) -> Self {
+        match value & 0x07 {
+            0 => HaltTime::Disabled,
+            1 => HaltTime::Seconds5,
+            2 => HaltTime::Seconds10,
+            3 => HaltTime::Seconds20,
+            4 => HaltTime::Seconds40,
+            5 => HaltTime::Seconds80,
+            6 => HaltTime::Seconds160,
+            _ => HaltTime::Infinite,
+        }
+    }
+}
+
+impl From<HaltTime> for u8 {
+    fn from(value: HaltTime) -> Self {
+        value as u8
+    }
+}
+
+register!(HaltTime, HALT_TIME);
+
+/// Strength of a first order filter, higher values filter more and respond slower.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum FilterBeta {
+    Beta1,
+    #[default]
+    Beta2,
+    Beta3,
+    Beta4,
+}
+
+impl FilterBeta {
+    fn from_bits(bits: u8) -> Self {
+        match bits & 0x03 {
+            0 => FilterBeta::Beta1,
+            1 => FilterBeta::Beta2,
+            2 => FilterBeta::Beta3,
+            _ => FilterBeta::Beta4,
+        }
+    }
+
+    fn bits(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Counts and LTA filter settings.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct FilterSettings {
+    pub counts_beta: FilterBeta,
+    pub lta_beta: FilterBeta,
+    /// The counts filter is disabled, the raw counts are reported.
+    pub counts_filter_disabled: bool,
+}
+
+impl From<u8> for FilterSettings {
+    fn from(value: u8) -> Self {
+        Self {
+            counts_beta: FilterBeta::from_bits(value),
+            lta_beta: FilterBeta::from_bits(value >> 2),
+            counts_filter_disabled: bit(value, 4),
+        }
+    }
+}
+
+impl From<FilterSettings> for u8 {
+    fn from(value: FilterSettings) -> Self {
+        let raw = value.counts_beta.bits() | (value.lta_beta.bits() << 2);
+        set_bit(raw, 4, value.counts_filter_disabled)
+    }
+}
+
+register!(FilterSettings, FILTER_SETTINGS);
+
+/// Shadow of one OTP bank, loaded from OTP at reset and writable over I2C.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct OtpShadow(pub u8);
+
+impl OtpShadow {
+    /// Bits of bank 0 selecting the I2C address, added to the default address 0x44.
+    pub const ADDRESS_MASK: u8 = 0b0000_0011;
+
+    /// Returns the address of the shadow register of OTP bank `bank`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bank` is not smaller than [`OTP_BANK_COUNT`].
+    pub const fn address(bank: usize) -> u8 {
+        assert!(bank < OTP_BANK_COUNT, "invalid OTP bank");
+        OTP_SHADOW_0 + bank as u8
+    }
+}
+
+impl From<u8> for OtpShadow {
+    fn from(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+impl From<OtpShadow> for u8 {
+    fn from(value: OtpShadow) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitfield_round_trip() {
+        for raw in [0x00u8, 0x9E, 0xFE] {
+            let flags = SystemFlags::from(raw);
+            assert_eq!(u8::from(flags), raw & 0b1001_0011);
+
+            let events = MainEvents::from(raw);
+            assert_eq!(u8::from(events), raw & 0x0F);
+
+            let power = PowerSettings::from(raw);
+            assert_eq!(u8::from(power), raw & 0x1F);
+        }
+
+        assert_eq!(PowerSettings::from(0x03).power_mode, PowerMode::UltraLowPower);
+        assert_eq!(ReportRate::Ms1024.millis(), 1024);
+
+        let multipliers = AtiMultipliers::from(0b1110_0101);
+        assert_eq!(multipliers.sensitivity, 0b0101);
+        assert_eq!(multipliers.compensation, 0b10);
+        assert_eq!(multipliers.compensation_high, 0b11);
+        assert_eq!(u8::from(multipliers), 0b1110_0101);
+    }
+
+    #[test]
+    fn test_threshold_validation() {
+        assert_eq!(ProxThreshold::new(0), Err(InvalidValue { register: PROX_THRESHOLD, value: 0 }));
+        assert_eq!(MovementThreshold::try_from(16), Err(InvalidValue { register: MOVEMENT_THRESHOLD, value: 16 }));
+        assert_eq!(MovementThreshold::new(15), Ok(MovementThreshold::MAX));
+        assert_eq!(TouchThreshold::new_const::<40>().get(), 40);
+        assert_eq!(MovementThreshold::from_raw(0xF3).map(MovementThreshold::get), Ok(0x03));
+        assert_eq!(ProxThreshold::from_raw(0x00), Err(InvalidValue { register: PROX_THRESHOLD, value: 0 }));
+    }
+
+    #[test]
+    fn test_quick_release_and_halt_time() {
+        let quick_release = QuickRelease::try_from(0x87).expect("Invalid");
+        assert!(quick_release.enabled);
+        assert_eq!(quick_release.threshold, QuickReleaseThreshold::new_const::<7>());
+        assert_eq!(u8::from(quick_release), 0x87);
+
+        let disabled = QuickRelease::try_from(0x00).expect("Invalid");
+        assert!(!disabled.enabled);
+        assert_eq!(disabled.threshold, QuickReleaseThreshold::MIN);
+        assert_eq!(QuickRelease::try_from(0x80), Err(InvalidValue { register: QUICK_RELEASE, value: 0 }));
+
+        assert_eq!(HaltTime::from(0x04), HaltTime::Seconds40);
+        assert_eq!(HaltTime::Seconds40.seconds(), Some(40));
+        assert_eq!(HaltTime::Infinite.seconds(), None);
+    }
+
+    #[test]
+    fn test_ati_target() {
+        assert_eq!(AtiTarget::from_counts(512), Ok(AtiTarget(64)));
+        assert_eq!(AtiTarget::from_counts(513), Err(InvalidValue { register: ATI_TARGET, value: 513 }));
+        assert_eq!(AtiTarget::from_counts(2048), Err(InvalidValue { register: ATI_TARGET, value: 2048 }));
+        assert_eq!(AtiTarget(255).counts(), 2040);
+    }
+
+    #[test]
+    fn test_config_block_layout() {
+        assert_eq!(CONFIG_START, 0x10);
+        assert_eq!(CONFIG_LEN, 9);
+        assert_eq!(OtpShadow::address(3), OTP_SHADOW_3);
+    }
+}
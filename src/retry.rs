@@ -0,0 +1,74 @@
+//! Retry policy for NACKed transactions.
+//!
+//! ProxSense devices only acknowledge I2C traffic inside their communication window,
+//! any transaction outside of it is NACKed. With a [`RetryPolicy`] the driver retries
+//! such transactions, waiting the configured backoff between attempts.
+
+use embedded_hal::i2c::ErrorKind;
+
+/// How often, and how fast, NACKed transactions are retried.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct RetryPolicy {
+    /// Maximum number of attempts per transaction, including the first one.
+    pub max_attempts: u8,
+    /// Time to wait before retrying a NACKed transaction, in microseconds.
+    pub backoff_us: u32,
+}
+
+impl RetryPolicy {
+    /// Never retry, every error is returned as is.
+    pub const NONE: RetryPolicy = RetryPolicy { max_attempts: 1, backoff_us: 0 };
+
+    /// Creates a retry policy.
+    pub const fn new(max_attempts: u8, backoff_us: u32) -> Self {
+        Self { max_attempts, backoff_us }
+    }
+
+    /// Returns `true` if a transaction failing with `kind` on attempt number `attempt`
+    /// (starting at 1) should be retried.
+    ///
+    /// Only NACKs are retried, other errors are not caused by the communication window.
+    pub fn should_retry(&self, attempt: u8, kind: ErrorKind) -> bool {
+        attempt < self.max_attempts && matches!(kind, ErrorKind::NoAcknowledge(_))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+/// Delay that returns immediately, used by the driver until a delay is provided with
+/// [`with_retry`](crate::Iqs231xDriver::with_retry) or [`with_ready_pin`](crate::Iqs231xDriver::with_ready_pin).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct NoDelay;
+
+impl embedded_hal::delay::DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::delay::DelayNs for NoDelay {
+    async fn delay_ns(&mut self, _ns: u32) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::retry::RetryPolicy;
+    use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+
+    #[test]
+    fn test_should_retry() {
+        let policy = RetryPolicy::new(3, 100);
+        let nack = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+
+        assert!(policy.should_retry(1, nack));
+        assert!(policy.should_retry(2, nack));
+        assert!(!policy.should_retry(3, nack));
+        assert!(!policy.should_retry(1, ErrorKind::Bus));
+        assert!(!RetryPolicy::NONE.should_retry(1, nack));
+    }
+}
@@ -0,0 +1,209 @@
+//! Discovery of IQS231 devices on a bus.
+
+use embedded_hal::i2c::SevenBitAddress;
+
+use crate::iqs231x::DeviceInfo;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::iqs231x::ADDR_RANGE;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::Iqs231xError;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use embedded_hal::i2c::ErrorKind;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use heapless::Vec;
+
+/// Number of addresses an IQS231A/B can use, the capacity of the list returned by [`scan`].
+pub const MAX_DEVICES: usize = 4;
+
+/// A device that answered during a scan.
+///
+/// Devices answering at the address with another product number are listed as well,
+/// see [`DeviceInfo::is_iqs231`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ScannedDevice {
+    pub address: SevenBitAddress,
+    pub info: DeviceInfo,
+}
+
+/// Scans the addresses an IQS231A/B can use, see [`ADDR_RANGE`].
+///
+/// # Example
+///
+/// ```rust
+/// use iqs231x_i2c::scan::scan;
+/// # use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+/// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+/// # let nack = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+/// # let mut i2c_interface = Mock::new(&[
+/// #     Transaction::write_read(0x44, vec![0x00], vec![0x00, 0x40, 0x06]),
+/// #     Transaction::write_read(0x45, vec![0x00], vec![0x00, 0x00, 0x00]).with_error(nack),
+/// #     Transaction::write_read(0x46, vec![0x00], vec![0x00, 0x00, 0x00]).with_error(nack),
+/// #     Transaction::write_read(0x47, vec![0x00], vec![0x00, 0x40, 0x0A]),
+/// # ]);
+///
+/// let devices = scan(&mut i2c_interface).unwrap();
+///
+/// assert_eq!(devices.len(), 2);
+/// assert_eq!(devices[1].address, 0x47);
+/// # i2c_interface.done();
+/// ```
+#[cfg(feature = "blocking")]
+pub fn scan<I2C, E>(i2c: &mut I2C) -> Result<Vec<ScannedDevice, MAX_DEVICES>, Iqs231xError<E>>
+where
+    I2C: embedded_hal::i2c::I2c<Error = E>,
+    E: embedded_hal::i2c::Error,
+{
+    scan_addresses(i2c, ADDR_RANGE)
+}
+
+/// Scans the given addresses in order, listing every device that reads out its device information.
+///
+/// Addresses that NACK are skipped, any other bus error ends the scan. Devices beyond
+/// the capacity `N` are not listed.
+#[cfg(feature = "blocking")]
+pub fn scan_addresses<I2C, E, const N: usize>(
+    i2c: &mut I2C,
+    addresses: impl IntoIterator<Item = SevenBitAddress>,
+) -> Result<Vec<ScannedDevice, N>, Iqs231xError<E>>
+where
+    I2C: embedded_hal::i2c::I2c<Error = E>,
+    E: embedded_hal::i2c::Error,
+{
+    let mut devices = Vec::new();
+
+    for address in addresses {
+        if devices.is_full() {
+            break;
+        }
+
+        let answer = crate::Iqs231xDriver::with_address(&mut *i2c, address).device_info();
+        push_answer(&mut devices, address, answer)?;
+    }
+
+    Ok(devices)
+}
+
+/// Async version of [`scan`].
+#[cfg(feature = "async")]
+pub async fn scan_async<I2C, E>(i2c: &mut I2C) -> Result<Vec<ScannedDevice, MAX_DEVICES>, Iqs231xError<E>>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    E: embedded_hal::i2c::Error,
+{
+    scan_addresses_async(i2c, ADDR_RANGE).await
+}
+
+/// Async version of [`scan_addresses`].
+#[cfg(feature = "async")]
+pub async fn scan_addresses_async<I2C, E, const N: usize>(
+    i2c: &mut I2C,
+    addresses: impl IntoIterator<Item = SevenBitAddress>,
+) -> Result<Vec<ScannedDevice, N>, Iqs231xError<E>>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    E: embedded_hal::i2c::Error,
+{
+    let mut devices = Vec::new();
+
+    for address in addresses {
+        if devices.is_full() {
+            break;
+        }
+
+        let answer = crate::asynch::Iqs231xDriver::with_address(&mut *i2c, address).device_info().await;
+        push_answer(&mut devices, address, answer)?;
+    }
+
+    Ok(devices)
+}
+
+/// Lists the device that gave `answer` at `address`, unless the address NACKed.
+#[cfg(any(feature = "blocking", feature = "async"))]
+fn push_answer<E, const N: usize>(
+    devices: &mut Vec<ScannedDevice, N>,
+    address: SevenBitAddress,
+    answer: Result<DeviceInfo, Iqs231xError<E>>,
+) -> Result<(), Iqs231xError<E>>
+where
+    E: embedded_hal::i2c::Error,
+{
+    let info = match answer {
+        Ok(info) => info,
+        Err(Iqs231xError::I2CError(e)) if matches!(e.kind(), ErrorKind::NoAcknowledge(_)) => return Ok(()),
+        Err(e) => return Err(e),
+    };
+
+    // Cannot fail, the scans stop once the list is full.
+    let _ = devices.push(ScannedDevice { address, info });
+
+    Ok(())
+}
+
+#[cfg(all(test, any(feature = "blocking", feature = "async")))]
+mod tests {
+    use crate::iqs231x::Variant;
+    use crate::Iqs231xError;
+    use alloc::vec;
+    use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+    use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_scan_addresses() {
+        use crate::scan::scan_addresses;
+
+        let nack = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+        let expectations = [
+            Transaction::write_read(0x44, vec![0x00], vec![0x00, 0x00, 0x00]).with_error(nack),
+            Transaction::write_read(0x45, vec![0x00], vec![0x00, 0x40, 0x0A]),
+            Transaction::write_read(0x46, vec![0x00], vec![0x00, 0x3C, 0x01]),
+            Transaction::write_read(0x44, vec![0x00], vec![0x00, 0x40, 0x06]),
+            Transaction::write_read(0x46, vec![0x00], vec![0x00, 0x00, 0x00]).with_error(ErrorKind::Bus),
+        ];
+
+        let mut mock = Mock::new(&expectations);
+
+        let devices = scan_addresses::<_, _, 4>(&mut mock, 0x44..=0x46).expect("Errored");
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].address, 0x45);
+        assert_eq!(devices[0].info.variant(), Some(Variant::Iqs231B));
+        assert!(!devices[1].info.is_iqs231());
+
+        let devices = scan_addresses::<_, _, 1>(&mut mock, [0x44, 0x45]).expect("Errored");
+        assert_eq!(devices.len(), 1);
+
+        let result = scan_addresses::<_, _, 4>(&mut mock, [0x46]);
+        assert_eq!(result, Err(Iqs231xError::I2CError(ErrorKind::Bus)));
+
+        mock.done();
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_scan_async() {
+        use crate::asynch::tests::block_on;
+        use crate::scan::{scan_addresses_async, scan_async};
+
+        let nack = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+        let expectations = [
+            Transaction::write_read(0x44, vec![0x00], vec![0x00, 0x40, 0x06]),
+            Transaction::write_read(0x45, vec![0x00], vec![0x00, 0x00, 0x00]).with_error(nack),
+            Transaction::write_read(0x46, vec![0x00], vec![0x00, 0x40, 0x0A]),
+            Transaction::write_read(0x47, vec![0x00], vec![0x00, 0x00, 0x00]).with_error(nack),
+            Transaction::write_read(0x45, vec![0x00], vec![0x00, 0x00, 0x00]).with_error(ErrorKind::Bus),
+        ];
+
+        let mut mock = Mock::new(&expectations);
+
+        let devices = block_on(scan_async(&mut mock)).expect("Errored");
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[1].address, 0x46);
+        assert_eq!(devices[1].info.variant(), Some(Variant::Iqs231B));
+
+        let result = block_on(scan_addresses_async::<_, _, 4>(&mut mock, [0x45]));
+        assert_eq!(result, Err(Iqs231xError::I2CError(ErrorKind::Bus)));
+
+        mock.done();
+    }
+}
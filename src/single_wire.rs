@@ -0,0 +1,265 @@
+//! Single-wire backend, reading the data the IQS231 streams on its IO line.
+//!
+//! On boards where only the IO line reaches the MCU, the device can stream its state
+//! instead of answering over I2C. The line idles high, each frame is sent as:
+//!
+//! - a start bit (low),
+//! - the system flags, main events, counts and LTA, laid out as the registers
+//!   [`SYSTEM_FLAGS`] to [`LTA`] (6 bytes),
+//! - a checksum, the wrapping sum of the 6 data bytes,
+//! - a stop bit (high).
+//!
+//! Bytes are sent most significant bit first, every bit lasting one bit period. The
+//! backend bit-bangs the line with an [`InputPin`] and a [`DelayNs`], sampling the
+//! middle of every bit.
+//!
+//! The frame layout and the default bit period follow the description of the single-wire
+//! data streaming in the Azoteq IQS231A/B datasheet. The backend needs no I2C bus and
+//! is available without any driver feature.
+//!
+//! [`SYSTEM_FLAGS`]: crate::registers::SYSTEM_FLAGS
+//! [`LTA`]: crate::registers::LTA
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::InputPin;
+
+use crate::error::SingleWireError;
+use crate::iqs231x::{ChannelData, Events};
+
+/// Default duration of one bit, in microseconds, as given by the datasheet.
+pub const DEFAULT_BIT_PERIOD_US: u32 = 100;
+
+/// Default time to wait for the start of a frame, in microseconds.
+pub const DEFAULT_FRAME_TIMEOUT_US: u32 = 100_000;
+
+/// Number of data bytes in a frame, excluding the checksum.
+pub const FRAME_LEN: usize = 6;
+
+/// The data of one frame.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Frame {
+    pub events: Events,
+    pub channel: ChannelData,
+}
+
+impl Frame {
+    fn from_bytes(bytes: [u8; FRAME_LEN]) -> Self {
+        let [flags, events, counts_high, counts_low, lta_high, lta_low] = bytes;
+
+        Self {
+            events: Events::from_bytes([flags, events]),
+            channel: ChannelData::from_bytes([counts_high, counts_low, lta_high, lta_low]),
+        }
+    }
+}
+
+/// Bit-banged receiver for the IO line of an IQS231.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct SingleWire<P, D> {
+    pin: P,
+    delay: D,
+    bit_period_us: u32,
+    timeout_us: u32,
+}
+
+impl<P, D> SingleWire<P, D> {
+    /// Creates a receiver reading the IO line `pin`, timed with `delay`.
+    pub fn new(pin: P, delay: D) -> Self {
+        Self {
+            pin,
+            delay,
+            bit_period_us: DEFAULT_BIT_PERIOD_US,
+            timeout_us: DEFAULT_FRAME_TIMEOUT_US,
+        }
+    }
+
+    /// Updates the duration of one bit, in microseconds.
+    pub fn set_bit_period_us(&mut self, bit_period_us: u32) {
+        self.bit_period_us = bit_period_us;
+    }
+
+    /// Updates how long to wait for the start of a frame, in microseconds.
+    pub fn set_timeout_us(&mut self, timeout_us: u32) {
+        self.timeout_us = timeout_us;
+    }
+
+    /// Destroys the receiver, returning the pin and the delay.
+    pub fn release(self) -> (P, D) {
+        (self.pin, self.delay)
+    }
+}
+
+impl<P, D> SingleWire<P, D>
+where
+    P: InputPin,
+    D: DelayNs,
+{
+    /// Waits for the next frame and reads it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use iqs231x_i2c::single_wire::SingleWire;
+    /// # use embedded_hal_mock::eh1::digital::{Mock, State, Transaction};
+    /// # let bytes = [0x00u8, 0x01, 0x01, 0xF4, 0x02, 0x00, 0xF8];
+    /// # let mut levels = vec![State::Low, State::Low];
+    /// # levels.extend(bytes.iter().flat_map(|byte| (0..8).rev().map(move |bit| {
+    /// #     if byte >> bit & 1 == 1 { State::High } else { State::Low }
+    /// # })));
+    /// # levels.push(State::High);
+    /// # let transactions: Vec<_> = levels.into_iter().map(Transaction::get).collect();
+    /// # let pin = Mock::new(&transactions);
+    /// # let delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    ///
+    /// let mut io = SingleWire::new(pin, delay);
+    /// let frame = io.read_frame().unwrap();
+    ///
+    /// assert!(frame.events.proximity);
+    /// assert_eq!(frame.channel.delta.0, 12);
+    /// # io.release().0.done();
+    /// ```
+    pub fn read_frame(&mut self) -> Result<Frame, SingleWireError<P::Error>> {
+        self.wait_for_start()?;
+
+        // Move to the middle of the start bit, and check that it was not a glitch.
+        self.delay.delay_us(self.bit_period_us / 2);
+        if self.pin.is_high().map_err(SingleWireError::Pin)? {
+            return Err(SingleWireError::Framing);
+        }
+
+        let mut bytes = [0; FRAME_LEN + 1];
+        for byte in bytes.iter_mut() {
+            *byte = self.read_byte()?;
+        }
+
+        self.delay.delay_us(self.bit_period_us);
+        if self.pin.is_low().map_err(SingleWireError::Pin)? {
+            return Err(SingleWireError::Framing);
+        }
+
+        trace!("iqs231x single-wire frame: {:?}", bytes);
+
+        let (data, checksum) = bytes.split_at(FRAME_LEN);
+        let expected = data.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+        if checksum[0] != expected {
+            return Err(SingleWireError::Checksum { expected, found: checksum[0] });
+        }
+
+        let mut data_bytes = [0; FRAME_LEN];
+        data_bytes.copy_from_slice(data);
+
+        Ok(Frame::from_bytes(data_bytes))
+    }
+
+    /// Reads the events of the next frame.
+    pub fn read_events(&mut self) -> Result<Events, SingleWireError<P::Error>> {
+        Ok(self.read_frame()?.events)
+    }
+
+    /// Reads the counts, LTA and delta of the next frame.
+    pub fn read_channel(&mut self) -> Result<ChannelData, SingleWireError<P::Error>> {
+        Ok(self.read_frame()?.channel)
+    }
+
+    /// Polls the line every quarter bit until it goes low.
+    fn wait_for_start(&mut self) -> Result<(), SingleWireError<P::Error>> {
+        let poll_us = (self.bit_period_us / 4).max(1);
+        let mut waited_us = 0;
+
+        while self.pin.is_high().map_err(SingleWireError::Pin)? {
+            if waited_us >= self.timeout_us {
+                return Err(SingleWireError::Timeout);
+            }
+
+            self.delay.delay_us(poll_us);
+            waited_us = waited_us.saturating_add(poll_us);
+        }
+
+        Ok(())
+    }
+
+    fn read_byte(&mut self) -> Result<u8, SingleWireError<P::Error>> {
+        let mut byte = 0;
+
+        for _ in 0..8 {
+            self.delay.delay_us(self.bit_period_us);
+            let bit = self.pin.is_high().map_err(SingleWireError::Pin)?;
+            byte = byte << 1 | u8::from(bit);
+        }
+
+        Ok(byte)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::SingleWireError;
+    use crate::iqs231x::{ChannelData, Events};
+    use crate::registers::{Counts, Lta};
+    use crate::single_wire::{Frame, SingleWire};
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use embedded_hal_mock::eh1::delay;
+    use embedded_hal_mock::eh1::digital::{Mock, State, Transaction};
+
+    fn frame_levels(bytes: &[u8]) -> Vec<Transaction> {
+        let mut levels = Vec::new();
+        levels.push(Transaction::get(State::Low));
+
+        for byte in bytes {
+            for bit in (0..8).rev() {
+                levels.push(Transaction::get(if byte >> bit & 1 == 1 { State::High } else { State::Low }));
+            }
+        }
+
+        levels.push(Transaction::get(State::High));
+        levels
+    }
+
+    #[test]
+    fn test_read_frame() {
+        let mut expectations = vec![Transaction::get(State::High), Transaction::get(State::Low)];
+        expectations.extend(frame_levels(&[0x80, 0b0000_0110, 0x03, 0x20, 0x03, 0x84, 0x30]));
+
+        let mut delays = vec![delay::Transaction::delay_us(25), delay::Transaction::delay_us(50)];
+        delays.extend((0..57).map(|_| delay::Transaction::delay_us(100)));
+
+        let pin = Mock::new(&expectations);
+        let delay = delay::CheckedDelay::new(&delays);
+
+        let mut io = SingleWire::new(pin, delay);
+        let frame = io.read_frame().expect("Errored");
+
+        assert_eq!(frame, Frame {
+            events: Events { touch: true, movement: true, device_reset: true, ..Default::default() },
+            channel: ChannelData::from_bytes([0x03, 0x20, 0x03, 0x84]),
+        });
+        assert_eq!(frame.channel.counts, Counts(800));
+        assert_eq!(frame.channel.lta, Lta(900));
+
+        let (mut pin, mut delay) = io.release();
+        pin.done();
+        delay.done();
+    }
+
+    #[test]
+    fn test_frame_errors() {
+        let mut expectations = vec![Transaction::get(State::High), Transaction::get(State::High)];
+        expectations.extend([Transaction::get(State::Low), Transaction::get(State::High), Transaction::get(State::Low)]);
+        expectations.extend(frame_levels(&[0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00]));
+
+        let pin = Mock::new(&expectations);
+        let delay = delay::NoopDelay::new();
+
+        let mut io = SingleWire::new(pin, delay);
+        io.set_timeout_us(25);
+
+        assert_eq!(io.read_frame(), Err(SingleWireError::Timeout));
+        assert_eq!(io.read_frame(), Err(SingleWireError::Framing));
+        assert_eq!(io.read_events(), Err(SingleWireError::Checksum { expected: 0x01, found: 0x00 }));
+
+        io.release().0.done();
+    }
+}
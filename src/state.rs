@@ -0,0 +1,54 @@
+//! Lifecycle states of the driver.
+//!
+//! The state is the last type parameter of [`Iqs231xDriver`](crate::Iqs231xDriver):
+//!
+//! - [`Uninitialized`]: returned by the constructors. Settings can be changed, see
+//!   [`configure`](crate::Iqs231xDriver::configure), or the whole start-up sequence run
+//!   with [`init`](crate::Iqs231xDriver::init).
+//! - [`Configured`]: a configuration was written, ATI has not run with it yet. See
+//!   [`start`](crate::Iqs231xDriver::start).
+//! - [`Running`]: ATI completed, events and counts can be read. Changing settings again
+//!   goes through [`reconfigure`](crate::Iqs231xDriver::reconfigure).
+
+use crate::Iqs231xError;
+
+/// Result of a fallible state transition: the driver in the next state `N`, or the
+/// driver `T` unchanged along with the error.
+pub type Transition<T, N, E> = Result<N, (T, Iqs231xError<E>)>;
+
+/// Time the device needs after power-up before it accepts communication, in microseconds.
+pub const POWER_UP_TIME_US: u32 = 20_000;
+
+/// ATI has to complete before the counts settle, in microseconds.
+pub const ATI_TIMEOUT_US: u32 = 1_000_000;
+
+/// Interval at which the ATI progress is polled, in microseconds.
+pub const ATI_POLL_INTERVAL_US: u32 = 10_000;
+
+/// The configuration of the device is unknown.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Uninitialized;
+
+/// A configuration was written, ATI has not run with it yet.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Configured;
+
+/// ATI completed, the device is sensing.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Running;
+
+/// States in which the settings of the device can be changed.
+pub trait Configurable: sealed::Sealed {}
+
+impl Configurable for Uninitialized {}
+impl Configurable for Configured {}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::Uninitialized {}
+    impl Sealed for super::Configured {}
+}